@@ -0,0 +1,86 @@
+extern crate rust_raytracer as raytracer;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use raytracer::{
+    bvh::BvhOptions,
+    group::Group,
+    point::Point3,
+    ray::Ray,
+    shape::Shape,
+    sphere::Sphere,
+    transformation::{scaling, translation},
+    triangle::Triangle,
+    world::World,
+};
+
+// A 10x10x10 grid of small spheres, each added to the world directly
+fn sphere_world() -> World {
+    let mut world = World::new();
+
+    for x in 0..10 {
+        for y in 0..10 {
+            for z in 0..10 {
+                let mut s = Sphere::new();
+                s.set_transform(translation(x as f64 - 5.0, y as f64 - 5.0, z as f64) * scaling(0.3, 0.3, 0.3));
+                world.add_object(Box::new(s));
+            }
+        }
+    }
+
+    world
+}
+
+// A wavy 50x50 quad height field, which is 5000 triangles in a single group
+fn mesh_world() -> World {
+    let size = 50;
+    let height = |x: usize, z: usize| ((x as f64) * 0.3).sin() * ((z as f64) * 0.2).cos();
+    let point = |x: usize, z: usize| Point3::new(x as f64 / 5.0 - 5.0, height(x, z), z as f64 / 5.0);
+    let mut mesh = Group::new();
+
+    for x in 0..size {
+        for z in 0..size {
+            mesh.add_child(Box::new(Triangle::new(point(x, z), point(x + 1, z), point(x + 1, z + 1))));
+            mesh.add_child(Box::new(Triangle::new(point(x, z), point(x + 1, z + 1), point(x, z + 1))));
+        }
+    }
+
+    let mut world = World::new();
+    world.add_object(Box::new(mesh));
+    world
+}
+
+fn cast_rays(world: &World) -> usize {
+    let origin = Point3::new(0.0, 5.0, -10.0);
+    let mut hits = 0;
+
+    for i in 0..10 {
+        for j in 0..10 {
+            let target = Point3::new(i as f64 - 5.0, -2.0 + j as f64 * 0.2, j as f64);
+            let r = Ray::new(origin, (target - origin).normalized());
+            hits += world.intersect(&r).len();
+        }
+    }
+
+    hits
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    for (name, mut world) in [("spheres", sphere_world()), ("mesh", mesh_world())] {
+        let mut group = c.benchmark_group(name);
+        group.sample_size(10);
+
+        group.bench_function("naive", |b| b.iter(|| cast_rays(&world)));
+
+        for (builder, options) in [("median", BvhOptions::new()), ("sah", BvhOptions::sah())] {
+            world.bvh_options = options;
+            world.build_bvh();
+            group.bench_function(builder, |b| b.iter(|| cast_rays(&world)));
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,282 @@
+use std::f64::consts::PI;
+use std::io::{self, BufRead, BufReader, Read};
+
+use crate::{color::Color, vector::Vector3};
+
+// What rays that don't hit anything see, looked up by the direction they were travelling in
+#[derive(Debug, Clone, PartialEq)]
+pub enum Background {
+    Constant(Color),
+    // Blends from `bottom` straight down to `top` straight up
+    Gradient { bottom: Color, top: Color },
+    Environment(EnvironmentMap),
+}
+
+impl Background {
+    pub fn color_at(&self, direction: Vector3) -> Color {
+        match self {
+            Background::Constant(color) => *color,
+            Background::Gradient { bottom, top } => {
+                let t = 0.5 * (direction.normalized().y + 1.0);
+                *bottom * (1.0 - t) + *top * t
+            }
+            Background::Environment(map) => map.color_at(direction),
+        }
+    }
+}
+
+// Equirectangular image of everything around the scene. The top row is straight up, the bottom row straight down,
+// and the centre of the image is along +z.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvironmentMap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Color>,
+}
+
+impl EnvironmentMap {
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert!(width > 0 && height > 0, "environment map needs at least one pixel");
+        assert_eq!(pixels.len(), width * height, "environment map needs width * height pixels");
+        EnvironmentMap { width, height, pixels }
+    }
+
+    // Radiance HDR (.hdr) import, with or without run-length encoded scanlines. Only the standard top to bottom,
+    // left to right orientation is supported.
+    pub fn parse<R: Read>(reader: R) -> io::Result<Self> {
+        let mut reader = BufReader::new(reader);
+        let mut line = String::new();
+
+        reader.read_line(&mut line)?;
+        if !line.starts_with("#?") {
+            return Err(invalid_data("not a Radiance HDR file"));
+        }
+
+        // Header variables until a blank line
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(invalid_data("missing resolution"));
+            }
+
+            match line.trim() {
+                "" => break,
+                header => {
+                    if let Some(format) = header.strip_prefix("FORMAT=") {
+                        if format != "32-bit_rle_rgbe" {
+                            return Err(invalid_data("unsupported pixel format"));
+                        }
+                    }
+                }
+            }
+        }
+
+        line.clear();
+        reader.read_line(&mut line)?;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let (height, width) = match tokens.as_slice() {
+            ["-Y", height, "+X", width] => match (height.parse::<usize>(), width.parse::<usize>()) {
+                (Ok(height), Ok(width)) if height > 0 && width > 0 => (height, width),
+                _ => return Err(invalid_data("invalid resolution")),
+            },
+            _ => return Err(invalid_data("unsupported orientation")),
+        };
+
+        let mut pixels = Vec::with_capacity(width * height);
+        let mut scanline = vec![[0u8; 4]; width];
+
+        for _ in 0..height {
+            read_scanline(&mut reader, &mut scanline)?;
+            pixels.extend(scanline.iter().map(rgbe_to_color));
+        }
+
+        Ok(EnvironmentMap::new(width, height, pixels))
+    }
+
+    pub fn color_at(&self, direction: Vector3) -> Color {
+        let d = direction.normalized();
+        let u = 0.5 + d.x.atan2(d.z) / (2.0 * PI);
+        let v = d.y.clamp(-1.0, 1.0).acos() / PI;
+
+        // Bilinear between the four nearest pixel centres, wrapping around horizontally
+        let x = u * self.width as f64 - 0.5;
+        let y = (v * self.height as f64 - 0.5).clamp(0.0, (self.height - 1) as f64);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (left, right) = (self.column(x0 as isize), self.column(x0 as isize + 1));
+        let (top, bottom) = (y0 as usize, (y0 as usize + 1).min(self.height - 1));
+
+        let upper = self.pixel(left, top) * (1.0 - fx) + self.pixel(right, top) * fx;
+        let lower = self.pixel(left, bottom) * (1.0 - fx) + self.pixel(right, bottom) * fx;
+        upper * (1.0 - fy) + lower * fy
+    }
+
+    fn column(&self, x: isize) -> usize {
+        x.rem_euclid(self.width as isize) as usize
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> Color {
+        self.pixels[y * self.width + x]
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_byte<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_scanline<R: Read>(reader: &mut R, scanline: &mut [[u8; 4]]) -> io::Result<()> {
+    let width = scanline.len();
+    let mut first = [0u8; 4];
+    reader.read_exact(&mut first)?;
+
+    // Run-length encoded scanlines start with 2, 2 and their width, anything else is flat RGBE pixels
+    if !(8..0x8000).contains(&width) || first[0] != 2 || first[1] != 2 || first[2] & 0x80 != 0 {
+        scanline[0] = first;
+        for pixel in scanline[1..].iter_mut() {
+            reader.read_exact(pixel)?;
+        }
+        return Ok(());
+    }
+
+    if ((first[2] as usize) << 8 | first[3] as usize) != width {
+        return Err(invalid_data("scanline width mismatch"));
+    }
+
+    // Each channel is encoded separately, as runs of a repeated byte or of literal bytes
+    for channel in 0..4 {
+        let mut x = 0;
+
+        while x < width {
+            let count = read_byte(reader)? as usize;
+            let (repeated, count) = if count > 128 { (true, count - 128) } else { (false, count) };
+
+            if count == 0 || x + count > width {
+                return Err(invalid_data("invalid run in scanline"));
+            }
+
+            if repeated {
+                let value = read_byte(reader)?;
+                for pixel in scanline[x..x + count].iter_mut() {
+                    pixel[channel] = value;
+                }
+            } else {
+                for pixel in scanline[x..x + count].iter_mut() {
+                    pixel[channel] = read_byte(reader)?;
+                }
+            }
+
+            x += count;
+        }
+    }
+
+    Ok(())
+}
+
+// Shared exponent, so the mantissas are scaled by 2^(e - 128) / 256
+fn rgbe_to_color(rgbe: &[u8; 4]) -> Color {
+    if rgbe[3] == 0 {
+        return Color::black();
+    }
+
+    let f = 2.0_f64.powi(rgbe[3] as i32 - 136);
+    Color::new(rgbe[0] as f64 * f, rgbe[1] as f64 * f, rgbe[2] as f64 * f)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    use super::*;
+
+    fn test_map() -> EnvironmentMap {
+        let pixels = (0..8).map(|i| Color::new(i as f64, 0.0, 0.0)).collect();
+        EnvironmentMap::new(4, 2, pixels)
+    }
+
+    #[test]
+    fn constant_background_is_the_same_everywhere() {
+        let background = Background::Constant(Color::new(0.2, 0.3, 0.4));
+
+        assert_eq!(background.color_at(Vector3::new(0.0, 1.0, 0.0)), Color::new(0.2, 0.3, 0.4));
+        assert_eq!(background.color_at(Vector3::new(1.0, -3.0, 2.0)), Color::new(0.2, 0.3, 0.4));
+    }
+
+    #[test]
+    fn gradient_background_blends_from_bottom_to_top() {
+        let background = Background::Gradient {
+            bottom: Color::black(),
+            top: Color::white(),
+        };
+
+        assert_eq!(background.color_at(Vector3::new(0.0, 1.0, 0.0)), Color::white());
+        assert_eq!(background.color_at(Vector3::new(0.0, -2.0, 0.0)), Color::black());
+        assert_eq!(background.color_at(Vector3::new(1.0, 0.0, 0.0)), Color::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn environment_map_at_pixel_centre() {
+        // Pixel (2, 0) is a quarter turn around from +z towards +x, and 45 degrees down from straight up
+        let color = test_map().color_at(Vector3::new(0.5, FRAC_1_SQRT_2, 0.5));
+
+        assert_eq!(color, Color::new(2.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn environment_map_wraps_around_horizontally() {
+        // Straight back along -z is the seam between the last and the first column
+        let color = test_map().color_at(Vector3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+
+        assert_eq!(color, Color::new(1.5, 0.0, 0.0));
+    }
+
+    #[test]
+    fn parsing_flat_hdr_file() {
+        let mut data = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\nEXPOSURE=1.0\n\n-Y 1 +X 2\n".to_vec();
+        data.extend([128, 64, 32, 129, 0, 0, 0, 0]);
+        let map = EnvironmentMap::parse(&data[..]).unwrap();
+
+        assert_eq!((map.width, map.height), (2, 1));
+        assert_eq!(map.pixels, vec![Color::new(1.0, 0.5, 0.25), Color::black()]);
+    }
+
+    #[test]
+    fn parsing_run_length_encoded_hdr_file() {
+        let mut data = b"#?RADIANCE\n\n-Y 1 +X 8\n".to_vec();
+        data.extend([2, 2, 0, 8]);
+        // A run for red and green, literal bytes for blue, and a run for the exponent
+        data.extend([136, 128, 136, 64]);
+        data.extend([8, 32, 32, 32, 32, 32, 32, 32, 32]);
+        data.extend([136, 129]);
+        let map = EnvironmentMap::parse(&data[..]).unwrap();
+
+        assert_eq!(map.pixels, vec![Color::new(1.0, 0.5, 0.25); 8]);
+    }
+
+    #[test]
+    fn parsing_rejects_other_files() {
+        assert!(EnvironmentMap::parse(&b"P3\n1 1\n255\n"[..]).is_err());
+        assert!(EnvironmentMap::parse(&b"#?RADIANCE\nFORMAT=32-bit_rle_xyze\n\n-Y 1 +X 1\n"[..]).is_err());
+        assert!(EnvironmentMap::parse(&b"#?RADIANCE\n\n+X 1 -Y 1\n\x80\x80\x80\x81"[..]).is_err());
+        // Truncated pixel data
+        assert!(EnvironmentMap::parse(&b"#?RADIANCE\n\n-Y 2 +X 1\n\x80\x80\x80\x81"[..]).is_err());
+    }
+
+    #[test]
+    fn parsing_rejects_files_without_pixels() {
+        assert!(EnvironmentMap::parse(&b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n"[..]).is_err());
+        assert!(EnvironmentMap::parse(&b"#?RADIANCE\n\n-Y 0 +X 0\n"[..]).is_err());
+        assert!(EnvironmentMap::parse(&b"#?RADIANCE\n\n-Y 1 +X 0\n"[..]).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "environment map needs at least one pixel")]
+    fn environment_map_without_pixels() {
+        EnvironmentMap::new(0, 0, Vec::new());
+    }
+}
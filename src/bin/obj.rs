@@ -0,0 +1,59 @@
+extern crate rust_raytracer as raytracer;
+
+use std::env;
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::Write;
+
+use raytracer::bvh::BvhOptions;
+use raytracer::camera::Camera;
+use raytracer::obj::ObjFile;
+use raytracer::shape::Shape;
+use raytracer::transformation::{self, view_transform};
+use raytracer::world::World;
+use raytracer::{color::*, light::*, plane::Plane, point::*, vector::*};
+
+fn main() {
+    let path = env::args().nth(1).expect("Usage: obj <path to .obj file>");
+    let file = File::open(&path).expect("Unable to open obj file");
+    let obj = ObjFile::parse(file).expect("Unable to read obj file");
+
+    println!("Loaded {} vertices from {}", obj.vertices.len(), path);
+    if obj.ignored_lines > 0 {
+        println!("Ignored {} unrecognized lines", obj.ignored_lines);
+    }
+
+    let mut model = obj.into_group();
+    model.set_transform(transformation::rotation_x(-PI / 2.0));
+
+    let mut floor = Plane::new();
+    floor.set_transform(transformation::translation(0.0, -1.0, 0.0));
+    floor.material.color = Color::new(1.0, 0.9, 0.9);
+    floor.material.specular = 0.0;
+
+    let mut world = World::with_bvh_options(BvhOptions::sah());
+    world.lights.push(PointLight::new(Point3::new(-10.0, 10.0, -10.0), Color::white()).into());
+    world.add_object(Box::new(floor));
+    model.build_bvh(&world.bvh_options);
+    if let Some(stats) = model.bvh_stats() {
+        println!(
+            "BVH depth {}, {} nodes, {:.2} triangles per leaf",
+            stats.depth, stats.node_count, stats.average_leaf_size
+        );
+    }
+    world.add_object(Box::new(model));
+
+    let mut camera = Camera::new(400, 200, PI / 3.0);
+    camera.set_transform(view_transform(
+        Point3::new(0.0, 1.5, -5.0),
+        Point3::new(0.0, 1.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    ));
+
+    let canvas_mutex = camera.render(&world);
+    let canvas = canvas_mutex.lock().unwrap();
+
+    println!("Starting to output ppm...");
+    let mut file = File::create("output/obj.ppm").expect("Unable to create file");
+    file.write_all(&canvas.to_ppm()[..]).expect("Unable to write data to file");
+}
@@ -3,18 +3,18 @@ extern crate rust_raytracer as raytracer;
 use std::fs::File;
 use std::io::Write;
 
-use raytracer::{canvas::*, color::*, tuple::*};
+use raytracer::{canvas::*, color::*, point::*, vector::*};
 
 #[derive(Debug)]
 struct Projectile {
-    position: Tuple,
-    velocity: Tuple,
+    position: Point3,
+    velocity: Vector3,
 }
 
 #[derive(Debug)]
 struct Environment {
-    gravity: Tuple,
-    wind: Tuple,
+    gravity: Vector3,
+    wind: Vector3,
 }
 
 enum Pixel {
@@ -23,11 +23,7 @@ enum Pixel {
 }
 
 impl Pixel {
-    pub fn from_point_for_canvas(point: Tuple, canvas: &Canvas) -> Pixel {
-        if !point.is_point() {
-            panic!("Given tuple is not a point. Point needed for conversion to screen space.");
-        }
-
+    pub fn from_point_for_canvas(point: Point3, canvas: &Canvas) -> Pixel {
         let rx = point.x.round();
         let ry = point.y.round();
 
@@ -55,12 +51,12 @@ fn main() {
     let mut canvas = Canvas::new(900, 500);
 
     let mut proj = Projectile {
-        position: Tuple::point(0.0, 1.0, 0.0),
-        velocity: Tuple::vector(1.0, 1.8, 0.0).normalized() * 11.25,
+        position: Point3::new(0.0, 1.0, 0.0),
+        velocity: Vector3::new(1.0, 1.8, 0.0).normalized() * 11.25,
     };
     let env = Environment {
-        gravity: Tuple::vector(0.0, -0.1, 0.0),
-        wind: Tuple::vector(-0.01, 0.0, 0.0),
+        gravity: Vector3::new(0.0, -0.1, 0.0),
+        wind: Vector3::new(-0.01, 0.0, 0.0),
     };
 
     let red = Color::red();
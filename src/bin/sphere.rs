@@ -6,7 +6,7 @@ use std::{fs::File, sync::Mutex};
 use indicatif::ProgressBar;
 use itertools::Itertools;
 use rayon::prelude::*;
-use raytracer::{canvas::*, color::*, intersection, light::*, material::*, ray::Ray, sphere::Sphere, tuple::*};
+use raytracer::{canvas::*, color::*, intersection, light::*, material::*, point::*, ray::Ray, shape::Shape, sphere::Sphere};
 
 fn main() {
     let mut sphere = Sphere::new();
@@ -15,12 +15,12 @@ fn main() {
     sphere.material.ambient = 0.6;
     sphere.material.shininess = 350.0;
 
-    let light = PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::white());
+    let light: Light = PointLight::new(Point3::new(-10.0, 10.0, -10.0), Color::white()).into();
 
     let background_color = Color::new(0.74, 0.93, 1.0);
 
     let canvas_pixels = 400;
-    let ray_origin = Tuple::point(0.0, 0.0, -5.0);
+    let ray_origin = Point3::new(0.0, 0.0, -5.0);
     let wall_size = 7.0;
     let wall_z = 10.0;
     let pixel_size = wall_size / canvas_pixels as f64;
@@ -38,18 +38,18 @@ fn main() {
         .for_each(|(x, y)| {
             let world_y = half - pixel_size * (y as f64);
             let world_x = -half + pixel_size * (x as f64);
-            let position = Tuple::point(world_x, world_y, wall_z);
+            let position = Point3::new(world_x, world_y, wall_z);
 
             let ray = Ray::new(ray_origin, (position - ray_origin).normalized());
             let xs = sphere.intersect(&ray);
 
             let mut canvas = canvas_mutex.lock().unwrap();
 
-            if intersection::hit(&xs) != None {
+            if intersection::hit(&xs).is_some() {
                 let point = ray.position(xs[0].t);
                 let normal = xs[0].object.normal_at(&point);
                 let eye = -ray.direction;
-                let color = xs[0].object.material.lighting(light, point, eye, normal);
+                let color = xs[0].object.material().lighting(xs[0].object, &light, point, eye, normal, 1.0);
 
                 canvas.write_pixel(x, y, &color);
             } else {
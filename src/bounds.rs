@@ -0,0 +1,275 @@
+use crate::{matrix::Matrix4, point::Point3, ray::Ray};
+
+// Axis-aligned bounding box. Bounds of infinite shapes (like planes) may contain infinite coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl BoundingBox {
+    pub fn new(min: Point3, max: Point3) -> Self {
+        BoundingBox { min, max }
+    }
+
+    // A box containing nothing, which grows to fit whatever is added to it
+    pub fn empty() -> Self {
+        BoundingBox {
+            min: Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            max: Point3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    pub fn is_finite(&self) -> bool {
+        [self.min.x, self.min.y, self.min.z, self.max.x, self.max.y, self.max.z]
+            .iter()
+            .all(|c| c.is_finite())
+    }
+
+    pub fn add_point(&mut self, point: &Point3) {
+        self.min = Point3::new(self.min.x.min(point.x), self.min.y.min(point.y), self.min.z.min(point.z));
+        self.max = Point3::new(self.max.x.max(point.x), self.max.y.max(point.y), self.max.z.max(point.z));
+    }
+
+    pub fn merge(&mut self, other: &BoundingBox) {
+        if !other.is_empty() {
+            self.add_point(&other.min);
+            self.add_point(&other.max);
+        }
+    }
+
+    pub fn contains_point(&self, point: &Point3) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y) && (self.min.z..=self.max.z).contains(&point.z)
+    }
+
+    pub fn contains_box(&self, other: &BoundingBox) -> bool {
+        self.contains_point(&other.min) && self.contains_point(&other.max)
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        Point3::new(
+            (self.min.x + self.max.x) / 2.0,
+            (self.min.y + self.max.y) / 2.0,
+            (self.min.z + self.max.z) / 2.0,
+        )
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+
+        let size = self.max - self.min;
+        2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    // The box around this box after transforming it. Works an axis at a time rather than transforming the eight
+    // corners, so that infinite bounds don't turn into NaN when multiplied by a zero in the matrix.
+    pub fn transform(&self, m: &Matrix4) -> BoundingBox {
+        if self.is_empty() {
+            return *self;
+        }
+
+        let min = [self.min.x, self.min.y, self.min.z];
+        let max = [self.max.x, self.max.y, self.max.z];
+        let mut new_min = [0.0; 3];
+        let mut new_max = [0.0; 3];
+
+        for i in 0..3 {
+            new_min[i] = m.at(i, 3);
+            new_max[i] = m.at(i, 3);
+
+            for j in 0..3 {
+                let factor = m.at(i, j);
+                if factor == 0.0 {
+                    continue;
+                }
+
+                let a = factor * min[j];
+                let b = factor * max[j];
+                new_min[i] += a.min(b);
+                new_max[i] += a.max(b);
+            }
+        }
+
+        BoundingBox::new(
+            Point3::new(new_min[0], new_min[1], new_min[2]),
+            Point3::new(new_max[0], new_max[1], new_max[2]),
+        )
+    }
+
+    // Slab test. Only tells whether the ray's line passes through the box, since intersections behind
+    // the ray's origin still matter when working out refractive indices.
+    pub fn intersects(&self, ray: &Ray) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        let axes = [
+            (self.min.x, self.max.x, ray.origin.x, ray.direction.x),
+            (self.min.y, self.max.y, ray.origin.y, ray.direction.y),
+            (self.min.z, self.max.z, ray.origin.z, ray.direction.z),
+        ];
+        let mut tmin = f64::NEG_INFINITY;
+        let mut tmax = f64::INFINITY;
+
+        for (min, max, origin, direction) in axes {
+            // A ray parallel to this axis' slab either always or never lies between its sides
+            if direction == 0.0 {
+                if origin < min || origin > max {
+                    return false;
+                }
+                continue;
+            }
+
+            let t0 = (min - origin) / direction;
+            let t1 = (max - origin) / direction;
+            tmin = tmin.max(t0.min(t1));
+            tmax = tmax.min(t0.max(t1));
+        }
+
+        tmin <= tmax
+    }
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        BoundingBox::empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_1_SQRT_2, PI, SQRT_2};
+
+    use super::*;
+    use crate::{
+        transformation::{rotation_x, rotation_y, scaling, translation},
+        vector::Vector3,
+    };
+
+    #[test]
+    fn creating_empty_bounding_box() {
+        let b = BoundingBox::empty();
+
+        assert!(b.is_empty());
+        assert_eq!(b.min.x, f64::INFINITY);
+        assert_eq!(b.max.x, f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn adding_points_to_empty_bounding_box() {
+        let mut b = BoundingBox::empty();
+        b.add_point(&Point3::new(-5.0, 2.0, 0.0));
+        b.add_point(&Point3::new(7.0, 0.0, -3.0));
+
+        assert_eq!(b.min, Point3::new(-5.0, 0.0, -3.0));
+        assert_eq!(b.max, Point3::new(7.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn merging_bounding_boxes() {
+        let mut b1 = BoundingBox::new(Point3::new(-5.0, -2.0, 0.0), Point3::new(7.0, 4.0, 4.0));
+        let b2 = BoundingBox::new(Point3::new(8.0, -7.0, -2.0), Point3::new(14.0, 2.0, 8.0));
+        b1.merge(&b2);
+
+        assert_eq!(b1.min, Point3::new(-5.0, -7.0, -2.0));
+        assert_eq!(b1.max, Point3::new(14.0, 4.0, 8.0));
+    }
+
+    #[test]
+    fn checking_whether_box_contains_point() {
+        let b = BoundingBox::new(Point3::new(5.0, -2.0, 0.0), Point3::new(11.0, 4.0, 7.0));
+
+        assert!(b.contains_point(&Point3::new(5.0, -2.0, 0.0)));
+        assert!(b.contains_point(&Point3::new(11.0, 4.0, 7.0)));
+        assert!(b.contains_point(&Point3::new(8.0, 1.0, 3.0)));
+        assert!(!b.contains_point(&Point3::new(3.0, 0.0, 3.0)));
+        assert!(!b.contains_point(&Point3::new(8.0, -4.0, 3.0)));
+        assert!(!b.contains_point(&Point3::new(8.0, 1.0, -1.0)));
+        assert!(!b.contains_point(&Point3::new(13.0, 1.0, 3.0)));
+        assert!(!b.contains_point(&Point3::new(8.0, 5.0, 3.0)));
+        assert!(!b.contains_point(&Point3::new(8.0, 1.0, 8.0)));
+    }
+
+    #[test]
+    fn checking_whether_box_contains_box() {
+        let b = BoundingBox::new(Point3::new(5.0, -2.0, 0.0), Point3::new(11.0, 4.0, 7.0));
+
+        assert!(b.contains_box(&BoundingBox::new(Point3::new(5.0, -2.0, 0.0), Point3::new(11.0, 4.0, 7.0))));
+        assert!(b.contains_box(&BoundingBox::new(Point3::new(6.0, -1.0, 1.0), Point3::new(10.0, 3.0, 6.0))));
+        assert!(!b.contains_box(&BoundingBox::new(Point3::new(4.0, -3.0, -1.0), Point3::new(10.0, 3.0, 6.0))));
+        assert!(!b.contains_box(&BoundingBox::new(Point3::new(6.0, -1.0, 1.0), Point3::new(12.0, 5.0, 8.0))));
+    }
+
+    #[test]
+    fn surface_area_of_bounding_box() {
+        let b = BoundingBox::new(Point3::new(-1.0, 0.0, 2.0), Point3::new(1.0, 3.0, 6.0));
+
+        assert_eq!(b.surface_area(), 2.0 * (2.0 * 3.0 + 3.0 * 4.0 + 4.0 * 2.0));
+        assert_eq!(BoundingBox::empty().surface_area(), 0.0);
+    }
+
+    #[test]
+    fn transforming_bounding_box() {
+        let b = BoundingBox::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        let b2 = b.transform(&(rotation_x(PI / 4.0) * rotation_y(PI / 4.0)));
+
+        assert_eq!(b2.min, Point3::new(-SQRT_2, -1.0 - FRAC_1_SQRT_2, -1.0 - FRAC_1_SQRT_2));
+        assert_eq!(b2.max, Point3::new(SQRT_2, 1.0 + FRAC_1_SQRT_2, 1.0 + FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn transforming_infinite_bounding_box() {
+        let b = BoundingBox::new(
+            Point3::new(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Point3::new(f64::INFINITY, 0.0, f64::INFINITY),
+        );
+        let b2 = b.transform(&(translation(0.0, 2.0, 0.0) * scaling(2.0, 2.0, 2.0)));
+
+        assert_eq!(b2.min.x, f64::NEG_INFINITY);
+        assert_eq!(b2.min.y, 2.0);
+        assert_eq!(b2.max.y, 2.0);
+        assert_eq!(b2.max.z, f64::INFINITY);
+    }
+
+    #[test]
+    fn intersecting_ray_with_bounding_box_at_origin() {
+        let b = BoundingBox::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        let cases = [
+            (Point3::new(5.0, 0.5, 0.0), Vector3::new(-1.0, 0.0, 0.0), true),
+            (Point3::new(-5.0, 0.5, 0.0), Vector3::new(1.0, 0.0, 0.0), true),
+            (Point3::new(0.5, 5.0, 0.0), Vector3::new(0.0, -1.0, 0.0), true),
+            (Point3::new(0.5, -5.0, 0.0), Vector3::new(0.0, 1.0, 0.0), true),
+            (Point3::new(0.5, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0), true),
+            (Point3::new(0.5, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0), true),
+            (Point3::new(0.0, 0.5, 0.0), Vector3::new(0.0, 0.0, 1.0), true),
+            (Point3::new(-2.0, 0.0, 0.0), Vector3::new(2.0, 4.0, 6.0), false),
+            (Point3::new(0.0, -2.0, 0.0), Vector3::new(6.0, 2.0, 4.0), false),
+            (Point3::new(0.0, 0.0, -2.0), Vector3::new(4.0, 6.0, 2.0), false),
+            (Point3::new(2.0, 0.0, 2.0), Vector3::new(0.0, 0.0, -1.0), false),
+            (Point3::new(0.0, 2.0, 2.0), Vector3::new(0.0, -1.0, 0.0), false),
+            (Point3::new(2.0, 2.0, 0.0), Vector3::new(-1.0, 0.0, 0.0), false),
+        ];
+
+        for (origin, direction, expected) in cases {
+            let r = Ray::new(origin, direction.normalized());
+            assert_eq!(b.intersects(&r), expected, "{:?}", r);
+        }
+    }
+
+    #[test]
+    fn intersecting_ray_with_flat_infinite_bounding_box() {
+        let b = BoundingBox::new(
+            Point3::new(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Point3::new(f64::INFINITY, 0.0, f64::INFINITY),
+        );
+
+        assert!(b.intersects(&Ray::new(Point3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2))));
+        assert!(!b.intersects(&Ray::new(Point3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0))));
+    }
+}
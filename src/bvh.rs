@@ -0,0 +1,424 @@
+use std::cmp::Ordering;
+
+use crate::{bounds::BoundingBox, point::Point3, ray::Ray};
+
+// Leaves are not split any further once they hold this many items
+pub const MAX_LEAF_SIZE: usize = 4;
+
+// Number of buckets candidate SAH splits are evaluated at, per axis
+pub const SAH_BINS: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitMethod {
+    // Splits at the median centroid along the longest axis. Quick to build, but struggles with unevenly spread items.
+    Median,
+    // Binned surface area heuristic, which places splits where they minimize the expected cost of traversal
+    Sah { bins: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BvhOptions {
+    pub split: SplitMethod,
+    pub max_leaf_size: usize,
+}
+
+impl BvhOptions {
+    pub fn new() -> Self {
+        BvhOptions {
+            split: SplitMethod::Median,
+            max_leaf_size: MAX_LEAF_SIZE,
+        }
+    }
+
+    pub fn sah() -> Self {
+        BvhOptions {
+            split: SplitMethod::Sah { bins: SAH_BINS },
+            max_leaf_size: MAX_LEAF_SIZE,
+        }
+    }
+}
+
+impl Default for BvhOptions {
+    fn default() -> Self {
+        BvhOptions::new()
+    }
+}
+
+// Shape of a built hierarchy, for tuning the options. Unbounded items aren't part of the tree and aren't counted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BvhStats {
+    pub depth: usize,
+    pub node_count: usize,
+    pub leaf_count: usize,
+    pub average_leaf_size: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Leaf { bounds: BoundingBox, start: usize, count: usize },
+    Interior { bounds: BoundingBox, left: usize, right: usize },
+}
+
+impl Node {
+    fn bounds(&self) -> &BoundingBox {
+        match self {
+            Node::Leaf { bounds, .. } | Node::Interior { bounds, .. } => bounds,
+        }
+    }
+}
+
+// Bounding volume hierarchy over a list of items, referred to by their index in that list. Items with infinite
+// bounds (like planes) can't be split sensibly, so they are kept out of the tree and handed out for every ray.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bvh {
+    nodes: Vec<Node>,
+    indices: Vec<usize>,
+    unbounded: Vec<usize>,
+    item_count: usize,
+}
+
+impl Bvh {
+    pub fn build(bounds: &[BoundingBox], options: &BvhOptions) -> Self {
+        let (mut indices, unbounded): (Vec<usize>, Vec<usize>) = (0..bounds.len()).partition(|&i| bounds[i].is_finite());
+        let mut nodes = Vec::new();
+
+        if !indices.is_empty() {
+            build_node(&mut nodes, bounds, &mut indices, 0, options);
+        }
+
+        Bvh {
+            nodes,
+            indices,
+            unbounded,
+            item_count: bounds.len(),
+        }
+    }
+
+    // Number of items the hierarchy was built over
+    pub fn len(&self) -> usize {
+        self.item_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.item_count == 0
+    }
+
+    pub fn stats(&self) -> BvhStats {
+        let leaf_count = self.nodes.iter().filter(|n| matches!(n, Node::Leaf { .. })).count();
+        let average_leaf_size = if leaf_count == 0 {
+            0.0
+        } else {
+            self.indices.len() as f64 / leaf_count as f64
+        };
+
+        BvhStats {
+            depth: if self.nodes.is_empty() { 0 } else { self.depth(0) },
+            node_count: self.nodes.len(),
+            leaf_count,
+            average_leaf_size,
+        }
+    }
+
+    // Calls `visit` with the index of every item whose bounding box the ray may pass through
+    pub fn traverse(&self, ray: &Ray, mut visit: impl FnMut(usize)) {
+        for &i in &self.unbounded {
+            visit(i);
+        }
+
+        if !self.nodes.is_empty() {
+            self.traverse_node(0, ray, &mut visit);
+        }
+    }
+
+    fn traverse_node(&self, node: usize, ray: &Ray, visit: &mut impl FnMut(usize)) {
+        if !self.nodes[node].bounds().intersects(ray) {
+            return;
+        }
+
+        match self.nodes[node] {
+            Node::Leaf { start, count, .. } => {
+                for &i in &self.indices[start..start + count] {
+                    visit(i);
+                }
+            }
+            Node::Interior { left, right, .. } => {
+                self.traverse_node(left, ray, visit);
+                self.traverse_node(right, ray, visit);
+            }
+        }
+    }
+
+    fn depth(&self, node: usize) -> usize {
+        match self.nodes[node] {
+            Node::Leaf { .. } => 1,
+            Node::Interior { left, right, .. } => 1 + self.depth(left).max(self.depth(right)),
+        }
+    }
+}
+
+// Splits `indices` in two until the leaves are small enough. `start` is the offset of `indices` within
+// the whole index list. Returns the index of the new node.
+fn build_node(nodes: &mut Vec<Node>, bounds: &[BoundingBox], indices: &mut [usize], start: usize, options: &BvhOptions) -> usize {
+    let mut node_bounds = BoundingBox::empty();
+    let mut centroid_bounds = BoundingBox::empty();
+
+    for &i in indices.iter() {
+        node_bounds.merge(&bounds[i]);
+        centroid_bounds.add_point(&bounds[i].centroid());
+    }
+
+    if indices.len() <= options.max_leaf_size.max(1) {
+        nodes.push(Node::Leaf {
+            bounds: node_bounds,
+            start,
+            count: indices.len(),
+        });
+        return nodes.len() - 1;
+    }
+
+    // The SAH split can't separate items whose centroids all fall in the same bin, the median split always can
+    let mid = match options.split {
+        SplitMethod::Median => None,
+        SplitMethod::Sah { bins } => sah_split(bounds, indices, &centroid_bounds, bins),
+    }
+    .unwrap_or_else(|| median_split(bounds, indices, &centroid_bounds));
+
+    // Reserve this node's slot before its children are added
+    let node = nodes.len();
+    nodes.push(Node::Leaf {
+        bounds: node_bounds,
+        start,
+        count: 0,
+    });
+
+    let (left_indices, right_indices) = indices.split_at_mut(mid);
+    let left = build_node(nodes, bounds, left_indices, start, options);
+    let right = build_node(nodes, bounds, right_indices, start + mid, options);
+    nodes[node] = Node::Interior {
+        bounds: node_bounds,
+        left,
+        right,
+    };
+
+    node
+}
+
+// Sorts `indices` by centroid along the longest axis and returns the middle
+fn median_split(bounds: &[BoundingBox], indices: &mut [usize], centroid_bounds: &BoundingBox) -> usize {
+    let extent = centroid_bounds.max - centroid_bounds.min;
+    let axis = if extent.x >= extent.y && extent.x >= extent.z {
+        0
+    } else if extent.y >= extent.z {
+        1
+    } else {
+        2
+    };
+
+    indices.sort_by(|&a, &b| {
+        let a = axis_value(&bounds[a].centroid(), axis);
+        let b = axis_value(&bounds[b].centroid(), axis);
+        a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+    });
+
+    indices.len() / 2
+}
+
+// Buckets the centroids along each axis and tries a split between every pair of neighbouring buckets, keeping
+// the one with the lowest surface area cost. Partitions `indices` around that split and returns where it is.
+fn sah_split(bounds: &[BoundingBox], indices: &mut [usize], centroid_bounds: &BoundingBox, bins: usize) -> Option<usize> {
+    let bins = bins.max(2);
+    let bin_of = |i: usize, axis: usize| {
+        let min = axis_value(&centroid_bounds.min, axis);
+        let extent = axis_value(&centroid_bounds.max, axis) - min;
+        let offset = (axis_value(&bounds[i].centroid(), axis) - min) / extent;
+        ((offset * bins as f64) as usize).min(bins - 1)
+    };
+
+    // (cost, axis, number of bins on the left)
+    let mut best: Option<(f64, usize, usize)> = None;
+
+    for axis in 0..3 {
+        if axis_value(&centroid_bounds.max, axis) - axis_value(&centroid_bounds.min, axis) <= 0.0 {
+            continue;
+        }
+
+        let mut bin_bounds = vec![BoundingBox::empty(); bins];
+        let mut bin_counts = vec![0; bins];
+        for &i in indices.iter() {
+            let bin = bin_of(i, axis);
+            bin_bounds[bin].merge(&bounds[i]);
+            bin_counts[bin] += 1;
+        }
+
+        // Sweep from the right first so each split's right side cost is known when sweeping from the left
+        let mut right_costs = vec![0.0; bins];
+        let mut right_bounds = BoundingBox::empty();
+        let mut right_count = 0;
+        for split in (1..bins).rev() {
+            right_bounds.merge(&bin_bounds[split]);
+            right_count += bin_counts[split];
+            right_costs[split] = right_count as f64 * right_bounds.surface_area();
+        }
+
+        let mut left_bounds = BoundingBox::empty();
+        let mut left_count = 0;
+        for split in 1..bins {
+            left_bounds.merge(&bin_bounds[split - 1]);
+            left_count += bin_counts[split - 1];
+            if left_count == 0 || left_count == indices.len() {
+                continue;
+            }
+
+            let cost = left_count as f64 * left_bounds.surface_area() + right_costs[split];
+            if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                best = Some((cost, axis, split));
+            }
+        }
+    }
+
+    let (_, axis, split) = best?;
+    let mut mid = 0;
+    for j in 0..indices.len() {
+        if bin_of(indices[j], axis) < split {
+            indices.swap(mid, j);
+            mid += 1;
+        }
+    }
+
+    Some(mid)
+}
+
+fn axis_value(point: &Point3, axis: usize) -> f64 {
+    match axis {
+        0 => point.x,
+        1 => point.y,
+        _ => point.z,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::Vector3;
+
+    fn unit_box_at(x: f64) -> BoundingBox {
+        BoundingBox::new(Point3::new(x - 0.5, -0.5, -0.5), Point3::new(x + 0.5, 0.5, 0.5))
+    }
+
+    fn visited(bvh: &Bvh, ray: &Ray) -> Vec<usize> {
+        let mut items = Vec::new();
+        bvh.traverse(ray, |i| items.push(i));
+        items.sort_unstable();
+        items
+    }
+
+    #[test]
+    fn building_bvh_over_nothing() {
+        let bvh = Bvh::build(&[], &BvhOptions::new());
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert!(bvh.is_empty());
+        assert!(visited(&bvh, &r).is_empty());
+    }
+
+    #[test]
+    fn small_lists_become_single_leaf() {
+        let bounds: Vec<BoundingBox> = (0..MAX_LEAF_SIZE).map(|i| unit_box_at(i as f64 * 2.0)).collect();
+        let bvh = Bvh::build(&bounds, &BvhOptions::new());
+
+        assert_eq!(bvh.nodes.len(), 1);
+        assert!(matches!(bvh.nodes[0], Node::Leaf { count, .. } if count == MAX_LEAF_SIZE));
+    }
+
+    #[test]
+    fn traversal_only_visits_items_near_ray() {
+        let bounds: Vec<BoundingBox> = (0..100).map(|i| unit_box_at(i as f64 * 2.0)).collect();
+        let bvh = Bvh::build(&bounds, &BvhOptions::new());
+        let r = Ray::new(Point3::new(40.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let items = visited(&bvh, &r);
+
+        assert_eq!(bvh.len(), 100);
+        assert!(items.contains(&20));
+        assert!(items.len() <= MAX_LEAF_SIZE);
+    }
+
+    #[test]
+    fn every_item_is_in_exactly_one_leaf() {
+        let bounds: Vec<BoundingBox> = (0..37).map(|i| unit_box_at(i as f64)).collect();
+        let bvh = Bvh::build(&bounds, &BvhOptions::new());
+        let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+
+        assert_eq!(visited(&bvh, &r), (0..37).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn unbounded_items_are_always_visited() {
+        let plane = BoundingBox::new(
+            Point3::new(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Point3::new(f64::INFINITY, 0.0, f64::INFINITY),
+        );
+        let bvh = Bvh::build(&[unit_box_at(0.0), plane], &BvhOptions::sah());
+        let r = Ray::new(Point3::new(10.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert_eq!(visited(&bvh, &r), vec![1]);
+    }
+
+    #[test]
+    fn sah_bvh_visits_every_item_exactly_once() {
+        let bounds: Vec<BoundingBox> = (0..37).map(|i| unit_box_at((i * i) as f64 * 0.1)).collect();
+        let bvh = Bvh::build(&bounds, &BvhOptions::sah());
+        let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+
+        assert_eq!(visited(&bvh, &r), (0..37).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn sah_bvh_with_unevenly_spread_items() {
+        // Ten tightly packed boxes and two far away ones
+        let mut bounds: Vec<BoundingBox> = (0..10).map(|i| unit_box_at(i as f64 * 0.01)).collect();
+        bounds.push(unit_box_at(100.0));
+        bounds.push(unit_box_at(200.0));
+        let options = BvhOptions {
+            split: SplitMethod::Sah { bins: 16 },
+            max_leaf_size: 2,
+        };
+        let bvh = Bvh::build(&bounds, &options);
+        let r = Ray::new(Point3::new(100.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert_eq!(visited(&bvh, &r), vec![10, 11]);
+    }
+
+    #[test]
+    fn identical_items_still_respect_leaf_size() {
+        let bounds = vec![unit_box_at(0.0); 9];
+        let bvh = Bvh::build(&bounds, &BvhOptions::sah());
+        let stats = bvh.stats();
+
+        assert_eq!(stats.leaf_count, 3);
+        assert!(stats.average_leaf_size <= MAX_LEAF_SIZE as f64);
+    }
+
+    #[test]
+    fn reporting_bvh_stats() {
+        let bounds: Vec<BoundingBox> = (0..8).map(|i| unit_box_at(i as f64 * 2.0)).collect();
+        let options = BvhOptions {
+            split: SplitMethod::Median,
+            max_leaf_size: 2,
+        };
+        let stats = Bvh::build(&bounds, &options).stats();
+
+        assert_eq!(stats.depth, 3);
+        assert_eq!(stats.node_count, 7);
+        assert_eq!(stats.leaf_count, 4);
+        assert_eq!(stats.average_leaf_size, 2.0);
+    }
+
+    #[test]
+    fn stats_of_empty_bvh() {
+        let stats = Bvh::build(&[], &BvhOptions::sah()).stats();
+
+        assert_eq!(stats.depth, 0);
+        assert_eq!(stats.node_count, 0);
+        assert_eq!(stats.average_leaf_size, 0.0);
+    }
+}
@@ -1,26 +1,64 @@
+use std::collections::VecDeque;
 use std::sync::Mutex;
 
 use indicatif::ProgressBar;
-use itertools::Itertools;
+use rand::{rngs::StdRng, SeedableRng};
 use rayon::prelude::*;
 
-use crate::{canvas::Canvas, matrix::Matrix, ray::Ray, tuple::Tuple, world::World};
+use crate::{
+    canvas::Canvas, color::Color, matrix::Matrix4, point::Point3, ray::Ray, sampling::SamplingOptions, transformation::Transform, world::World,
+};
+
+// A sample's position on the image plane, in pixels from the top-left corner, and the color its ray saw
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    x: f64,
+    y: f64,
+    color: Color,
+}
+
+// Filter-weighted sum of the samples that count towards a pixel
+#[derive(Debug, Clone, Copy)]
+struct PixelSum {
+    color: Color,
+    weight: f64,
+}
+
+impl PixelSum {
+    fn color(&self) -> Color {
+        if self.weight <= 0.0 {
+            return Color::black();
+        }
+
+        self.color * (1.0 / self.weight)
+    }
+}
+
+impl Default for PixelSum {
+    fn default() -> Self {
+        PixelSum {
+            color: Color::black(),
+            weight: 0.0,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Camera {
     pub hsize: usize,
     pub vsize: usize,
     pub field_of_view: f64,
-    pub transform: Matrix,
+    transform: Transform,
     pub half_width: f64,
     pub half_height: f64,
     pub pixel_size: f64,
+    pub sampling: SamplingOptions,
 }
 
 impl Camera {
     pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Self {
         let half_view = (field_of_view / 2.0).tan();
-        let aspect = (hsize as f64 / vsize as f64) as f64;
+        let aspect = hsize as f64 / vsize as f64;
         let half_width: f64;
         let half_height: f64;
 
@@ -38,50 +76,201 @@ impl Camera {
             hsize,
             vsize,
             field_of_view,
-            transform: Matrix::identity(),
+            transform: Transform::identity(),
             half_width,
             half_height,
             pixel_size,
+            sampling: SamplingOptions::new(),
         }
     }
 
+    pub fn transform(&self) -> &Matrix4 {
+        self.transform.matrix()
+    }
+
+    pub fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = Transform::new(transform);
+    }
+
     pub fn ray_for_pixel(&self, px: usize, py: usize) -> Ray {
-        let xoffset = (px as f64 + 0.5) * self.pixel_size;
-        let yoffset = (py as f64 + 0.5) * self.pixel_size;
+        self.ray_for_pixel_offset(px, py, 0.5, 0.5)
+    }
+
+    // Ray through a spot within the pixel, dx and dy being between 0 and 1 from its top-left corner
+    pub fn ray_for_pixel_offset(&self, px: usize, py: usize, dx: f64, dy: f64) -> Ray {
+        let xoffset = (px as f64 + dx) * self.pixel_size;
+        let yoffset = (py as f64 + dy) * self.pixel_size;
 
         let world_x = self.half_width - xoffset;
         let world_y = self.half_height - yoffset;
 
-        let pixel = self.transform.inverse() * Tuple::point(world_x, world_y, -1.0);
-        let origin = self.transform.inverse() * Tuple::point(0.0, 0.0, 0.0);
+        let inverse = self.transform.inverse();
+        let pixel = inverse * Point3::new(world_x, world_y, -1.0);
+        let origin = inverse * Point3::origin();
         let direction = (pixel - origin).normalized();
 
         Ray::new(origin, direction)
     }
 
     pub fn render(&self, world: &World) -> Mutex<Canvas> {
-        println!("Raytracing {} pixels...", self.vsize * self.hsize);
-        let progress = ProgressBar::new((self.vsize * self.hsize) as u64);
+        self.render_with_refined_pixels(world).0
+    }
+
+    // Also returns a mask of the pixels adaptive sampling took extra samples for, white where it did.
+    // Without adaptive sampling the mask is all black.
+    pub fn render_with_refined_pixels(&self, world: &World) -> (Mutex<Canvas>, Canvas) {
+        let pixels = self.vsize * self.hsize;
+        println!("Raytracing {} pixels...", pixels);
+        let progress = ProgressBar::new(pixels as u64);
         progress.set_draw_rate(5);
 
-        let canvas_mutex = Mutex::new(Canvas::new(self.hsize, self.vsize));
+        // Adaptive sampling starts out with a single sample per pixel, which is kept for the pixels that aren't refined
+        let mut first_samples = Vec::new();
+        let mut refined = vec![false; pixels];
+
+        if let Some(adaptive) = self.sampling.adaptive {
+            first_samples = (0..pixels)
+                .into_par_iter()
+                .map(|i| {
+                    let sample = self.sample_pixel(world, i % self.hsize, i / self.hsize, 1)[0];
+                    progress.inc(1);
+                    sample
+                })
+                .collect();
+
+            refined = (0..pixels)
+                .into_par_iter()
+                .map(|i| self.has_contrast(&first_samples, i % self.hsize, i / self.hsize, adaptive.threshold))
+                .collect();
+
+            let count = refined.iter().filter(|r| **r).count();
+            println!("Refining {} pixels...", count);
+            progress.inc_length(count as u64);
+        }
 
-        (0..self.hsize) // x
-            .cartesian_product(0..self.vsize) // y
-            .par_bridge()
-            .for_each(|(x, y)| {
-                let ray = self.ray_for_pixel(x, y);
-                let color = world.color_at(&ray);
+        // Each row's samples are added to the rows within the filter's reach as soon as they're traced, so only the
+        // sums of those rows are kept around instead of every sample in the image
+        let reach = self.filter_reach();
+        let mut canvas = Canvas::new(self.hsize, self.vsize);
+        let mut window: VecDeque<Vec<PixelSum>> = VecDeque::new();
+        let mut window_start = 0;
+
+        for py in 0..self.vsize {
+            let row: Vec<Vec<Sample>> = (0..self.hsize)
+                .into_par_iter()
+                .map(|px| {
+                    let i = py * self.hsize + px;
+                    let count = match self.sampling.adaptive {
+                        Some(_) if !refined[i] => return vec![first_samples[i]],
+                        Some(adaptive) => adaptive.max_samples,
+                        None => self.sampling.samples_per_pixel,
+                    };
+                    let samples = self.sample_pixel(world, px, py, count);
+                    progress.inc(1);
+                    samples
+                })
+                .collect();
+
+            while window_start + window.len() < (py + reach + 1).min(self.vsize) {
+                window.push_back(vec![PixelSum::default(); self.hsize]);
+            }
+
+            window
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(k, sums)| self.add_samples(&row, sums, window_start + k));
+
+            // Rows that no later row can reach are finished
+            while window_start + reach <= py || (py + 1 == self.vsize && !window.is_empty()) {
+                let sums = window.pop_front().unwrap();
+
+                for (x, sum) in sums.iter().enumerate() {
+                    canvas.write_pixel(x, window_start, &sum.color());
+                }
+
+                window_start += 1;
+            }
+        }
 
-                let mut canvas = canvas_mutex.lock().unwrap();
-                canvas.write_pixel(x, y, &color);
+        progress.finish();
 
-                progress.inc(1);
-            });
+        let mut refined_mask = Canvas::new(self.hsize, self.vsize);
+        for (i, _) in refined.iter().enumerate().filter(|(_, r)| **r) {
+            refined_mask.write_pixel(i % self.hsize, i / self.hsize, &Color::white());
+        }
 
-        progress.finish();
+        (Mutex::new(canvas), refined_mask)
+    }
+
+    fn sample_pixel(&self, world: &World, px: usize, py: usize, count: usize) -> Vec<Sample> {
+        // Seeded per pixel so the result doesn't depend on which thread got to the pixel first
+        let mut rng = StdRng::seed_from_u64(self.sampling.pixel_seed(py * self.hsize + px));
+        let options = SamplingOptions {
+            samples_per_pixel: count,
+            ..self.sampling
+        };
+
+        options
+            .offsets(&mut rng)
+            .into_iter()
+            .map(|(dx, dy)| {
+                let ray = self.ray_for_pixel_offset(px, py, dx, dy);
+
+                Sample {
+                    x: px as f64 + dx,
+                    y: py as f64 + dy,
+                    color: world.color_at(&ray, world.max_depth),
+                }
+            })
+            .collect()
+    }
+
+    // Whether any of the pixel's eight neighbours differs from it by more than the threshold in one of the channels,
+    // going by the single sample every pixel gets before refining
+    fn has_contrast(&self, first_samples: &[Sample], px: usize, py: usize, threshold: f64) -> bool {
+        let color = first_samples[py * self.hsize + px].color;
+
+        for y in py.saturating_sub(1)..(py + 2).min(self.vsize) {
+            for x in px.saturating_sub(1)..(px + 2).min(self.hsize) {
+                let other = first_samples[y * self.hsize + x].color;
+                let difference = (color.r - other.r).abs().max((color.g - other.g).abs()).max((color.b - other.b).abs());
+
+                if difference > threshold {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    // How many pixels away samples can still count towards a pixel. Samples of the pixel k over are at least k - 0.5
+    // from this one's centre.
+    fn filter_reach(&self) -> usize {
+        (self.sampling.filter.radius() + 0.5).ceil() as usize - 1
+    }
 
-        canvas_mutex
+    // Adds the samples of one row of pixels that are within the filter's reach to the sums of row py. Each pixel's
+    // samples share a single pixel's worth of weight, so neighbours that adaptive sampling refined don't outweigh the
+    // pixel itself.
+    fn add_samples(&self, row: &[Vec<Sample>], sums: &mut [PixelSum], py: usize) {
+        let filter = self.sampling.filter;
+        let reach = self.filter_reach();
+        let cy = py as f64 + 0.5;
+
+        for (px, sum) in sums.iter_mut().enumerate() {
+            let cx = px as f64 + 0.5;
+
+            for pixel_samples in &row[px.saturating_sub(reach)..(px + reach + 1).min(self.hsize)] {
+                let share = 1.0 / pixel_samples.len() as f64;
+
+                for sample in pixel_samples {
+                    let weight = filter.weight(sample.x - cx, sample.y - cy) * share;
+                    sum.color = sum.color + sample.color * weight;
+                    sum.weight += weight;
+                }
+            }
+        }
     }
 }
 
@@ -90,9 +279,10 @@ mod tests {
     use std::f64::consts::{PI, SQRT_2};
 
     use crate::{
-        color::Color,
+        sampling::{AdaptiveOptions, Filter, SampleStrategy},
         transformation::{self, view_transform},
         utils::floats_equal,
+        vector::Vector3,
     };
 
     use super::*;
@@ -107,7 +297,7 @@ mod tests {
         assert_eq!(c.hsize, 160);
         assert_eq!(c.vsize, 120);
         assert!(floats_equal(c.field_of_view, PI / 2.0));
-        assert_eq!(c.transform, Matrix::identity());
+        assert_eq!(*c.transform(), Matrix4::identity());
     }
 
     #[test]
@@ -129,8 +319,8 @@ mod tests {
         let c = Camera::new(201, 101, PI / 2.0);
         let r = c.ray_for_pixel(100, 50);
 
-        assert_eq!(r.origin, Tuple::point(0.0, 0.0, 0.0));
-        assert_eq!(r.direction, Tuple::vector(0.0, 0.0, -1.0));
+        assert_eq!(r.origin, Point3::new(0.0, 0.0, 0.0));
+        assert_eq!(r.direction, Vector3::new(0.0, 0.0, -1.0));
     }
 
     #[test]
@@ -138,31 +328,150 @@ mod tests {
         let c = Camera::new(201, 101, PI / 2.0);
         let r = c.ray_for_pixel(0, 0);
 
-        assert_eq!(r.origin, Tuple::point(0.0, 0.0, 0.0));
-        assert_eq!(r.direction, Tuple::vector(0.66519, 0.33259, -0.66851));
+        assert_eq!(r.origin, Point3::new(0.0, 0.0, 0.0));
+        assert_eq!(r.direction, Vector3::new(0.66519, 0.33259, -0.66851));
     }
 
     #[test]
     fn consturct_ray_when_the_camera_is_transformed() {
         let mut c = Camera::new(201, 101, PI / 2.0);
-        c.transform = transformation::rotation_y(PI / 4.0) * transformation::translation(0.0, -2.0, 5.0);
+        c.set_transform(transformation::rotation_y(PI / 4.0) * transformation::translation(0.0, -2.0, 5.0));
         let r = c.ray_for_pixel(100, 50);
 
-        assert_eq!(r.origin, Tuple::point(0.0, 2.0, -5.0));
-        assert_eq!(r.direction, Tuple::vector(SQRT_2 / 2.0, 0.0, -SQRT_2 / 2.0));
+        assert_eq!(r.origin, Point3::new(0.0, 2.0, -5.0));
+        assert_eq!(r.direction, Vector3::new(SQRT_2 / 2.0, 0.0, -SQRT_2 / 2.0));
     }
 
     #[test]
     fn rendering_a_world_with_a_camera() {
         let w = World::default();
         let mut c = Camera::new(11, 11, PI / 2.0);
-        let from = Tuple::point(0.0, 0.0, -5.0);
-        let to = Tuple::point(0.0, 0.0, 0.0);
-        let up = Tuple::vector(0.0, 1.0, 0.0);
-        c.transform = view_transform(from, to, up);
+        let from = Point3::new(0.0, 0.0, -5.0);
+        let to = Point3::new(0.0, 0.0, 0.0);
+        let up = Vector3::new(0.0, 1.0, 0.0);
+        c.set_transform(view_transform(from, to, up));
         let canvas_mutex = c.render(&w);
         let canvas = canvas_mutex.lock().unwrap();
 
         assert_eq!(canvas.pixel_at(5, 5), Color::new(0.38066, 0.47583, 0.2855));
     }
+
+    fn test_camera(sampling: SamplingOptions) -> Camera {
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        let from = Point3::new(0.0, 0.0, -5.0);
+        let to = Point3::new(0.0, 0.0, 0.0);
+        let up = Vector3::new(0.0, 1.0, 0.0);
+        c.set_transform(view_transform(from, to, up));
+        c.sampling = sampling;
+        c
+    }
+
+    #[test]
+    fn ray_through_pixel_offset() {
+        let c = Camera::new(201, 101, PI / 2.0);
+
+        assert_eq!(c.ray_for_pixel_offset(100, 50, 0.5, 0.5).direction, c.ray_for_pixel(100, 50).direction);
+        assert_eq!(
+            c.ray_for_pixel_offset(99, 50, 1.0, 0.5).direction,
+            c.ray_for_pixel_offset(100, 50, 0.0, 0.5).direction
+        );
+    }
+
+    #[test]
+    fn supersampling_with_box_filter_averages_samples_within_pixel() {
+        let w = World::default();
+        let c = test_camera(SamplingOptions::supersampled(4, SampleStrategy::Regular, Filter::Box));
+        let canvas_mutex = c.render(&w);
+        let canvas = canvas_mutex.lock().unwrap();
+
+        // On the edge of the outer sphere, so the samples see different things
+        let (x, y) = (5, 4);
+        let mut expected = Color::black();
+        for (dx, dy) in [(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)] {
+            expected = expected + w.color_at(&c.ray_for_pixel_offset(x, y, dx, dy), w.max_depth) * 0.25;
+        }
+
+        assert_eq!(canvas.pixel_at(x, y), expected);
+        assert_ne!(canvas.pixel_at(x, y), w.color_at(&c.ray_for_pixel(x, y), w.max_depth));
+    }
+
+    #[test]
+    fn adaptive_sampling_leaves_flat_regions_alone() {
+        let w = World::new();
+        let c = test_camera(SamplingOptions::adaptive(SampleStrategy::Jittered, Filter::Box, AdaptiveOptions::new()));
+        let (_, refined) = c.render_with_refined_pixels(&w);
+
+        for y in 0..11 {
+            for x in 0..11 {
+                assert_eq!(refined.pixel_at(x, y), Color::black());
+            }
+        }
+    }
+
+    #[test]
+    fn adaptive_sampling_refines_edges() {
+        let w = World::default();
+        let adaptive = AdaptiveOptions {
+            threshold: 0.1,
+            max_samples: 4,
+        };
+        let c = test_camera(SamplingOptions::adaptive(SampleStrategy::Regular, Filter::Box, adaptive));
+        let (canvas_mutex, refined) = c.render_with_refined_pixels(&w);
+        let canvas = canvas_mutex.lock().unwrap();
+        let uniform = test_camera(SamplingOptions::supersampled(4, SampleStrategy::Regular, Filter::Box))
+            .render(&w)
+            .into_inner()
+            .unwrap();
+
+        // Background far from the sphere only gets its first sample
+        assert_eq!(refined.pixel_at(0, 0), Color::black());
+        assert_eq!(canvas.pixel_at(0, 0), w.color_at(&c.ray_for_pixel(0, 0), w.max_depth));
+
+        // The edge of the sphere gets the full set
+        assert_eq!(refined.pixel_at(5, 4), Color::white());
+        assert_eq!(canvas.pixel_at(5, 4), uniform.pixel_at(5, 4));
+    }
+
+    #[test]
+    fn rendering_with_same_seed_is_reproducible() {
+        let w = World::default();
+        let mut sampling = SamplingOptions::supersampled(4, SampleStrategy::Jittered, Filter::Mitchell);
+        sampling.seed = 3;
+        let c = test_camera(sampling);
+        let a = c.render(&w).into_inner().unwrap();
+        let b = c.render(&w).into_inner().unwrap();
+
+        for y in 0..11 {
+            for x in 0..11 {
+                assert_eq!(a.pixel_at(x, y), b.pixel_at(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn refined_neighbours_do_not_outweigh_the_pixel() {
+        let c = test_camera(SamplingOptions::supersampled(1, SampleStrategy::Regular, Filter::Tent));
+        let mut row: Vec<Vec<Sample>> = (0..11)
+            .map(|x| {
+                vec![Sample {
+                    x: x as f64 + 0.5,
+                    y: 5.5,
+                    color: Color::black(),
+                }]
+            })
+            .collect();
+        // The pixel to the right took 16 white samples, all 0.75 from the centre of pixel (5, 5)
+        let refined = Sample {
+            x: 6.25,
+            y: 5.5,
+            color: Color::white(),
+        };
+        row[6] = vec![refined; 16];
+
+        let mut sums = vec![PixelSum::default(); 11];
+        c.add_samples(&row, &mut sums, 5);
+
+        // Weighs as much as a single sample would, 0.25 against the pixel's own 1
+        assert_eq!(sums[5].color(), Color::new(0.2, 0.2, 0.2));
+    }
 }
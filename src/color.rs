@@ -38,6 +38,8 @@ impl Color {
         Color { r: 1.0, g: 1.0, b: 0.0 }
     }
 
+    // Unlike clamp, min and max turn NaN channels into 1 instead of passing them through
+    #[allow(clippy::manual_clamp)]
     pub fn clamped(&self) -> Self {
         Color::new(self.r.min(1.0).max(0.0), self.g.min(1.0).max(0.0), self.b.min(1.0).max(0.0))
     }
@@ -0,0 +1,313 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::Intersection;
+use crate::material::Material;
+use crate::point::Point3;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeTransforms};
+use crate::utils::EPSILON;
+use crate::vector::Vector3;
+
+// Double-napped cone around the y axis with its tip at the origin, optionally truncated and capped
+#[derive(Debug, PartialEq)]
+pub struct Cone {
+    transforms: ShapeTransforms,
+    pub material: Material,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Cone {
+    pub fn new() -> Self {
+        Cone {
+            transforms: ShapeTransforms::new(),
+            material: Material::new(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+
+    // Whether the intersection at t is within the cone's radius at height y
+    fn check_cap(ray: &Ray, t: f64, y: f64) -> bool {
+        let x = ray.origin.x + t * ray.direction.x;
+        let z = ray.origin.z + t * ray.direction.z;
+        (x * x + z * z) <= y * y
+    }
+
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        if !self.closed || ray.direction.y.abs() < EPSILON {
+            return;
+        }
+
+        // Bounds at infinity don't have caps
+        for bound in [self.minimum, self.maximum].iter().filter(|bound| bound.is_finite()) {
+            let t = (bound - ray.origin.y) / ray.direction.y;
+            if Cone::check_cap(ray, t, *bound) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+
+    fn push_if_in_bounds<'a>(&'a self, ray: &Ray, t: f64, xs: &mut Vec<Intersection<'a>>) {
+        let y = ray.origin.y + t * ray.direction.y;
+        if self.minimum < y && y < self.maximum {
+            xs.push(Intersection::new(t, self));
+        }
+    }
+}
+
+impl Shape for Cone {
+    fn transforms(&self) -> &ShapeTransforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut ShapeTransforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let mut xs = Vec::new();
+        let (o, d) = (ray.origin, ray.direction);
+        let a = d.x * d.x - d.y * d.y + d.z * d.z;
+        let b = 2.0 * o.x * d.x - 2.0 * o.y * d.y + 2.0 * o.z * d.z;
+        let c = o.x * o.x - o.y * o.y + o.z * o.z;
+
+        if a.abs() < EPSILON {
+            // The ray is parallel to one of the cone's halves, so it hits the other one at most once
+            if b.abs() >= EPSILON {
+                self.push_if_in_bounds(ray, -c / (2.0 * b), &mut xs);
+            }
+        } else {
+            let discriminant = (b * b) - (4.0 * a * c);
+
+            if discriminant < 0.0 {
+                // No hits
+                return xs;
+            }
+
+            let mut t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+            let mut t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            self.push_if_in_bounds(ray, t0, &mut xs);
+            self.push_if_in_bounds(ray, t1, &mut xs);
+        }
+
+        self.intersect_caps(ray, &mut xs);
+
+        xs
+    }
+
+    fn local_normal_at(&self, point: &Point3) -> Vector3 {
+        let dist = point.x * point.x + point.z * point.z;
+
+        if dist < self.maximum * self.maximum && point.y >= self.maximum - EPSILON {
+            Vector3::new(0.0, 1.0, 0.0)
+        } else if dist < self.minimum * self.minimum && point.y <= self.minimum + EPSILON {
+            Vector3::new(0.0, -1.0, 0.0)
+        } else {
+            let mut y = dist.sqrt();
+            if point.y > 0.0 {
+                y = -y;
+            }
+            Vector3::new(point.x, y, point.z)
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let limit = self.minimum.abs().max(self.maximum.abs());
+        BoundingBox::new(Point3::new(-limit, self.minimum, -limit), Point3::new(limit, self.maximum, limit))
+    }
+}
+
+impl Default for Cone {
+    fn default() -> Self {
+        Cone::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+
+    use super::*;
+
+    use crate::{transformation::translation, utils::floats_equal};
+
+    #[test]
+    fn default_minimum_maximum_and_closed_for_cone() {
+        let shape = Cone::new();
+
+        assert_eq!(shape.minimum, f64::NEG_INFINITY);
+        assert_eq!(shape.maximum, f64::INFINITY);
+        assert!(!shape.closed);
+    }
+
+    #[test]
+    fn intersecting_cone_with_ray() {
+        let shape = Cone::new();
+        let cases = [
+            (Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 5.0, 5.0),
+            (Point3::new(0.0, 0.0, -5.0), Vector3::new(1.0, 1.0, 1.0), 8.66025, 8.66025),
+            (Point3::new(1.0, 1.0, -5.0), Vector3::new(-0.5, -1.0, 1.0), 4.55006, 49.44994),
+        ];
+
+        for (origin, direction, t0, t1) in cases.iter() {
+            let r = Ray::new(*origin, direction.normalized());
+            let xs = shape.local_intersect(&r);
+
+            assert_eq!(xs.len(), 2);
+            assert!(floats_equal(xs[0].t, *t0));
+            assert!(floats_equal(xs[1].t, *t1));
+        }
+    }
+
+    #[test]
+    fn intersecting_cone_with_ray_parallel_to_one_of_its_halves() {
+        let shape = Cone::new();
+        let r = Ray::new(Point3::new(0.0, 0.0, -1.0), Vector3::new(0.0, 1.0, 1.0).normalized());
+        let xs = shape.local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert!(floats_equal(xs[0].t, 0.35355));
+    }
+
+    #[test]
+    fn intersecting_cone_end_caps() {
+        let mut shape = Cone::new();
+        shape.minimum = -0.5;
+        shape.maximum = 0.5;
+        shape.closed = true;
+        let cases = [
+            (Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 1.0, 0.0), 0),
+            (Point3::new(0.0, 0.0, -0.25), Vector3::new(0.0, 1.0, 1.0), 2),
+            (Point3::new(0.0, 0.0, -0.25), Vector3::new(0.0, 1.0, 0.0), 4),
+        ];
+
+        for (origin, direction, count) in cases.iter() {
+            let r = Ray::new(*origin, direction.normalized());
+            let xs = shape.local_intersect(&r);
+
+            assert_eq!(xs.len(), *count);
+        }
+    }
+
+    #[test]
+    fn ray_misses_cone() {
+        let shape = Cone::new();
+        let r = Ray::new(Point3::new(2.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert_eq!(shape.local_intersect(&r).len(), 0);
+    }
+
+    #[test]
+    fn intersecting_truncated_uncapped_cone() {
+        let mut shape = Cone::new();
+        shape.minimum = -0.5;
+        shape.maximum = 0.5;
+        let cases = [
+            // Would hit the cone above the maximum
+            (Point3::new(0.0, 1.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+            (Point3::new(0.0, 0.25, -5.0), Vector3::new(0.0, 0.0, 1.0), 2),
+            // Straight up through where the caps would be
+            (Point3::new(0.0, 0.0, -0.25), Vector3::new(0.0, 1.0, 0.0), 2),
+        ];
+
+        for (origin, direction, count) in cases.iter() {
+            let r = Ray::new(*origin, *direction);
+            let xs = shape.local_intersect(&r);
+
+            assert_eq!(xs.len(), *count);
+        }
+    }
+
+    #[test]
+    fn cone_end_cap_hits() {
+        let mut shape = Cone::new();
+        shape.minimum = -0.5;
+        shape.maximum = 0.5;
+        shape.closed = true;
+        let r = Ray::new(Point3::new(0.0, 0.0, -0.25), Vector3::new(0.0, 1.0, 0.0));
+        let mut xs = shape.local_intersect(&r);
+        xs.sort_by(|a, b| a.t.total_cmp(&b.t));
+
+        assert_eq!(xs.len(), 4);
+        assert!(floats_equal(xs[0].t, -0.5));
+        assert!(floats_equal(xs[1].t, -0.25));
+        assert!(floats_equal(xs[2].t, 0.25));
+        assert!(floats_equal(xs[3].t, 0.5));
+    }
+
+    #[test]
+    fn closed_cone_without_bounds_has_no_caps() {
+        let mut shape = Cone::new();
+        shape.closed = true;
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(1.0, 1.0, 1.0).normalized());
+        let xs = shape.local_intersect(&r);
+
+        assert_eq!(xs.len(), 2);
+        assert!(xs.iter().all(|x| x.t.is_finite()));
+    }
+
+    #[test]
+    fn intersecting_transformed_cone() {
+        let mut shape = Cone::new();
+        shape.set_transform(translation(1.0, 0.0, 0.0));
+        let r = Ray::new(Point3::new(1.0, 0.5, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = shape.intersect(&r);
+
+        assert_eq!(xs.len(), 2);
+        assert!(floats_equal(xs[0].t, 4.5));
+        assert!(floats_equal(xs[1].t, 5.5));
+    }
+
+    #[test]
+    fn computing_normal_vector_on_cone() {
+        let shape = Cone::new();
+        let cases = [
+            (Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0)),
+            (Point3::new(1.0, 1.0, 1.0), Vector3::new(1.0, -SQRT_2, 1.0)),
+            (Point3::new(-1.0, -1.0, 0.0), Vector3::new(-1.0, 1.0, 0.0)),
+        ];
+
+        for (point, normal) in cases.iter() {
+            assert_eq!(shape.local_normal_at(point), *normal);
+        }
+    }
+
+    #[test]
+    fn normal_vector_on_cone_end_caps() {
+        let mut shape = Cone::new();
+        shape.minimum = -1.0;
+        shape.maximum = 2.0;
+        shape.closed = true;
+        let cases = [
+            (Point3::new(0.5, 2.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Point3::new(0.5, -1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+        ];
+
+        for (point, normal) in cases.iter() {
+            assert_eq!(shape.local_normal_at(point), *normal);
+        }
+    }
+
+    #[test]
+    fn normal_on_transformed_cone() {
+        let mut shape = Cone::new();
+        shape.set_transform(translation(0.0, 1.0, 0.0));
+        let n = shape.normal_at(&Point3::new(1.0, 2.0, 0.0));
+
+        assert_eq!(n, Vector3::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2, 0.0));
+    }
+}
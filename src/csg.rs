@@ -0,0 +1,321 @@
+use std::cmp::Ordering;
+
+use crate::bounds::BoundingBox;
+use crate::bvh::BvhOptions;
+use crate::intersection::Intersection;
+use crate::material::Material;
+use crate::matrix::Matrix4;
+use crate::point::Point3;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeTransforms};
+use crate::vector::Vector3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl Operation {
+    // Whether a hit on the left (or right) child survives, given whether the ray is currently inside each child
+    pub fn intersection_allowed(&self, left_hit: bool, inside_left: bool, inside_right: bool) -> bool {
+        match self {
+            Operation::Union => (left_hit && !inside_right) || (!left_hit && !inside_left),
+            Operation::Intersection => (left_hit && inside_right) || (!left_hit && inside_left),
+            Operation::Difference => (left_hit && !inside_right) || (!left_hit && inside_left),
+        }
+    }
+}
+
+// Constructive solid geometry, combining two shapes into one. Like groups, the transform is pushed down to both children.
+#[derive(Debug)]
+pub struct Csg {
+    pub operation: Operation,
+    transforms: ShapeTransforms,
+    pub material: Material,
+    left: Box<dyn Shape>,
+    right: Box<dyn Shape>,
+}
+
+impl Csg {
+    pub fn new(operation: Operation, left: Box<dyn Shape>, right: Box<dyn Shape>) -> Self {
+        let mut csg = Csg {
+            operation,
+            transforms: ShapeTransforms::new(),
+            material: Material::new(),
+            left,
+            right,
+        };
+        csg.update_children();
+        csg
+    }
+
+    pub fn union(left: Box<dyn Shape>, right: Box<dyn Shape>) -> Self {
+        Csg::new(Operation::Union, left, right)
+    }
+
+    pub fn intersection(left: Box<dyn Shape>, right: Box<dyn Shape>) -> Self {
+        Csg::new(Operation::Intersection, left, right)
+    }
+
+    pub fn difference(left: Box<dyn Shape>, right: Box<dyn Shape>) -> Self {
+        Csg::new(Operation::Difference, left, right)
+    }
+
+    pub fn left(&self) -> &dyn Shape {
+        self.left.as_ref()
+    }
+
+    pub fn right(&self) -> &dyn Shape {
+        self.right.as_ref()
+    }
+
+    // Keeps only the intersections that lie on the surface of the combined shape. Expects `xs` to be sorted by t.
+    pub fn filter_intersections<'a>(&self, xs: &[Intersection<'a>]) -> Vec<Intersection<'a>> {
+        let mut inside_left = false;
+        let mut inside_right = false;
+        let mut result = Vec::new();
+
+        for i in xs {
+            let left_hit = self.left.includes(i.object);
+
+            if self.operation.intersection_allowed(left_hit, inside_left, inside_right) {
+                result.push(*i);
+            }
+
+            if left_hit {
+                inside_left = !inside_left;
+            } else {
+                inside_right = !inside_right;
+            }
+        }
+
+        result
+    }
+
+    fn update_children(&mut self) {
+        let world_transform = *self.world_transform();
+        self.left.set_parent_transform(world_transform);
+        self.right.set_parent_transform(world_transform);
+    }
+}
+
+impl PartialEq for Csg {
+    fn eq(&self, other: &Self) -> bool {
+        self.operation == other.operation
+            && self.transforms == other.transforms
+            && self.material == other.material
+            && self.left.as_ref() == other.left.as_ref()
+            && self.right.as_ref() == other.right.as_ref()
+    }
+}
+
+impl Shape for Csg {
+    fn transforms(&self) -> &ShapeTransforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut ShapeTransforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transforms.set_local(transform);
+        self.update_children();
+    }
+
+    fn set_parent_transform(&mut self, transform: Matrix4) {
+        self.transforms.set_parent(transform);
+        self.update_children();
+    }
+
+    fn build_bvh(&mut self, options: &BvhOptions) {
+        self.left.build_bvh(options);
+        self.right.build_bvh(options);
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.left.includes(other) || self.right.includes(other)
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let mut xs = self.left.intersect(ray);
+        xs.extend(self.right.intersect(ray));
+        xs.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap_or(Ordering::Equal));
+        self.filter_intersections(&xs)
+    }
+
+    fn local_normal_at(&self, _point: &Point3) -> Vector3 {
+        // Intersections always report the child that was hit, never the CSG itself
+        panic!("CSG shapes have no normals of their own, use the normal of the child that was hit");
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = self.left.parent_space_bounds();
+        bounds.merge(&self.right.parent_space_bounds());
+        bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cube::Cube,
+        group::Group,
+        shape::same_shape,
+        sphere::Sphere,
+        transformation::{scaling, translation},
+        utils::floats_equal,
+    };
+
+    #[test]
+    fn csg_is_created_with_operation_and_two_shapes() {
+        let c = Csg::union(Box::new(Sphere::new()), Box::new(Cube::new()));
+
+        assert_eq!(c.operation, Operation::Union);
+        assert!(c.left().as_any().is::<Sphere>());
+        assert!(c.right().as_any().is::<Cube>());
+    }
+
+    #[test]
+    fn evaluating_rule_for_csg_operation() {
+        let cases = [
+            (Operation::Union, true, true, true, false),
+            (Operation::Union, true, true, false, true),
+            (Operation::Union, true, false, true, false),
+            (Operation::Union, true, false, false, true),
+            (Operation::Union, false, true, true, false),
+            (Operation::Union, false, true, false, false),
+            (Operation::Union, false, false, true, true),
+            (Operation::Union, false, false, false, true),
+            (Operation::Intersection, true, true, true, true),
+            (Operation::Intersection, true, true, false, false),
+            (Operation::Intersection, true, false, true, true),
+            (Operation::Intersection, true, false, false, false),
+            (Operation::Intersection, false, true, true, true),
+            (Operation::Intersection, false, true, false, true),
+            (Operation::Intersection, false, false, true, false),
+            (Operation::Intersection, false, false, false, false),
+            (Operation::Difference, true, true, true, false),
+            (Operation::Difference, true, true, false, true),
+            (Operation::Difference, true, false, true, false),
+            (Operation::Difference, true, false, false, true),
+            (Operation::Difference, false, true, true, true),
+            (Operation::Difference, false, true, false, true),
+            (Operation::Difference, false, false, true, false),
+            (Operation::Difference, false, false, false, false),
+        ];
+
+        for (op, lhit, inl, inr, expected) in cases {
+            assert_eq!(op.intersection_allowed(lhit, inl, inr), expected, "{:?} {} {} {}", op, lhit, inl, inr);
+        }
+    }
+
+    #[test]
+    fn filtering_list_of_intersections() {
+        let cases = [(Operation::Union, 0, 3), (Operation::Intersection, 1, 2), (Operation::Difference, 0, 1)];
+
+        for (op, x0, x1) in cases {
+            let c = Csg::new(op, Box::new(Sphere::new()), Box::new(Cube::new()));
+            let (s1, s2) = (c.left(), c.right());
+            let xs = [
+                Intersection::new(1.0, s1),
+                Intersection::new(2.0, s2),
+                Intersection::new(3.0, s1),
+                Intersection::new(4.0, s2),
+            ];
+            let result = c.filter_intersections(&xs);
+
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0], xs[x0]);
+            assert_eq!(result[1], xs[x1]);
+        }
+    }
+
+    #[test]
+    fn ray_misses_csg_object() {
+        let c = Csg::union(Box::new(Sphere::new()), Box::new(Cube::new()));
+        let r = Ray::new(Point3::new(0.0, 2.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = c.local_intersect(&r);
+
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn ray_hits_csg_object() {
+        let mut s2 = Sphere::new();
+        s2.set_transform(translation(0.0, 0.0, 0.5));
+        let c = Csg::union(Box::new(Sphere::new()), Box::new(s2));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = c.local_intersect(&r);
+
+        assert_eq!(xs.len(), 2);
+        assert!(floats_equal(xs[0].t, 4.0));
+        assert!(same_shape(xs[0].object, c.left()));
+        assert!(floats_equal(xs[1].t, 6.5));
+        assert!(same_shape(xs[1].object, c.right()));
+    }
+
+    #[test]
+    fn drilling_hole_through_cube() {
+        let mut drill = Sphere::new();
+        drill.set_transform(scaling(0.5, 0.5, 2.0));
+        let c = Csg::difference(Box::new(Cube::new()), Box::new(drill));
+
+        // Straight down the hole
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        assert!(c.local_intersect(&r).is_empty());
+
+        // Through the solid part of the cube
+        let r = Ray::new(Point3::new(0.8, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = c.local_intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert!(floats_equal(xs[0].t, 4.0));
+        assert!(floats_equal(xs[1].t, 6.0));
+    }
+
+    #[test]
+    fn csg_children_can_be_groups() {
+        let mut g = Group::new();
+        g.add_child(Box::new(Sphere::new()));
+        let mut s = Sphere::new();
+        s.set_transform(translation(0.0, 0.0, 0.5));
+        let c = Csg::intersection(Box::new(g), Box::new(s));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = c.local_intersect(&r);
+
+        assert_eq!(xs.len(), 2);
+        assert!(floats_equal(xs[0].t, 4.5));
+        assert!(floats_equal(xs[1].t, 6.0));
+    }
+
+    #[test]
+    fn transforming_csg_updates_children() {
+        let mut c = Csg::union(Box::new(Sphere::new()), Box::new(Cube::new()));
+        c.set_transform(scaling(2.0, 2.0, 2.0));
+
+        assert_eq!(*c.left().parent_transform(), scaling(2.0, 2.0, 2.0));
+        assert_eq!(*c.right().parent_transform(), scaling(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn csg_has_bounding_box_that_contains_its_children() {
+        let mut right = Sphere::new();
+        right.set_transform(translation(2.0, 3.0, 4.0));
+        let c = Csg::difference(Box::new(Sphere::new()), Box::new(right));
+        let b = c.bounds();
+
+        assert_eq!(b.min, Point3::new(-1.0, -1.0, -1.0));
+        assert_eq!(b.max, Point3::new(3.0, 4.0, 5.0));
+    }
+}
@@ -0,0 +1,194 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::Intersection;
+use crate::material::Material;
+use crate::point::Point3;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeTransforms};
+use crate::utils::EPSILON;
+use crate::vector::Vector3;
+
+// Axis-aligned cube spanning -1 to 1 on every axis in object space
+#[derive(Debug, PartialEq)]
+pub struct Cube {
+    transforms: ShapeTransforms,
+    pub material: Material,
+}
+
+impl Cube {
+    pub fn new() -> Self {
+        Cube {
+            transforms: ShapeTransforms::new(),
+            material: Material::new(),
+        }
+    }
+}
+
+// Where a ray enters and leaves the slab between -1 and 1 along one axis
+fn check_axis(origin: f64, direction: f64) -> (f64, f64) {
+    let tmin_numerator = -1.0 - origin;
+    let tmax_numerator = 1.0 - origin;
+
+    let (tmin, tmax) = if direction.abs() >= EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (tmin_numerator * f64::INFINITY, tmax_numerator * f64::INFINITY)
+    };
+
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+impl Shape for Cube {
+    fn transforms(&self) -> &ShapeTransforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut ShapeTransforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x);
+        let (ytmin, ytmax) = check_axis(ray.origin.y, ray.direction.y);
+        let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z);
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+
+        if tmin > tmax {
+            // No hits
+            Vec::new()
+        } else {
+            vec![Intersection::new(tmin, self), Intersection::new(tmax, self)]
+        }
+    }
+
+    fn local_normal_at(&self, point: &Point3) -> Vector3 {
+        // The face being hit is the one on the axis with the largest component
+        let maxc = point.x.abs().max(point.y.abs()).max(point.z.abs());
+
+        if maxc == point.x.abs() {
+            Vector3::new(point.x, 0.0, 0.0)
+        } else if maxc == point.y.abs() {
+            Vector3::new(0.0, point.y, 0.0)
+        } else {
+            Vector3::new(0.0, 0.0, point.z)
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0))
+    }
+}
+
+impl Default for Cube {
+    fn default() -> Self {
+        Cube::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_1_SQRT_2, PI, SQRT_2};
+
+    use super::*;
+
+    use crate::{
+        transformation::{rotation_y, scaling, translation},
+        utils::floats_equal,
+    };
+
+    #[test]
+    fn ray_intersects_cube() {
+        let c = Cube::new();
+        let cases = [
+            (Point3::new(5.0, 0.5, 0.0), Vector3::new(-1.0, 0.0, 0.0), 4.0, 6.0),
+            (Point3::new(-5.0, 0.5, 0.0), Vector3::new(1.0, 0.0, 0.0), 4.0, 6.0),
+            (Point3::new(0.5, 5.0, 0.0), Vector3::new(0.0, -1.0, 0.0), 4.0, 6.0),
+            (Point3::new(0.5, -5.0, 0.0), Vector3::new(0.0, 1.0, 0.0), 4.0, 6.0),
+            (Point3::new(0.5, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0), 4.0, 6.0),
+            (Point3::new(0.5, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 4.0, 6.0),
+            (Point3::new(0.0, 0.5, 0.0), Vector3::new(0.0, 0.0, 1.0), -1.0, 1.0),
+        ];
+
+        for (origin, direction, t1, t2) in cases.iter() {
+            let r = Ray::new(*origin, *direction);
+            let xs = c.local_intersect(&r);
+
+            assert_eq!(xs.len(), 2);
+            assert!(floats_equal(xs[0].t, *t1));
+            assert!(floats_equal(xs[1].t, *t2));
+        }
+    }
+
+    #[test]
+    fn ray_misses_cube() {
+        let c = Cube::new();
+        let cases = [
+            (Point3::new(-2.0, 0.0, 0.0), Vector3::new(0.2673, 0.5345, 0.8018)),
+            (Point3::new(0.0, -2.0, 0.0), Vector3::new(0.8018, 0.2673, 0.5345)),
+            (Point3::new(0.0, 0.0, -2.0), Vector3::new(0.5345, 0.8018, 0.2673)),
+            (Point3::new(2.0, 0.0, 2.0), Vector3::new(0.0, 0.0, -1.0)),
+            (Point3::new(0.0, 2.0, 2.0), Vector3::new(0.0, -1.0, 0.0)),
+            (Point3::new(2.0, 2.0, 0.0), Vector3::new(-1.0, 0.0, 0.0)),
+        ];
+
+        for (origin, direction) in cases.iter() {
+            let r = Ray::new(*origin, *direction);
+            let xs = c.local_intersect(&r);
+
+            assert_eq!(xs.len(), 0);
+        }
+    }
+
+    #[test]
+    fn normal_on_surface_of_cube() {
+        let c = Cube::new();
+        let cases = [
+            (Point3::new(1.0, 0.5, -0.8), Vector3::new(1.0, 0.0, 0.0)),
+            (Point3::new(-1.0, -0.2, 0.9), Vector3::new(-1.0, 0.0, 0.0)),
+            (Point3::new(-0.4, 1.0, -0.1), Vector3::new(0.0, 1.0, 0.0)),
+            (Point3::new(0.3, -1.0, -0.7), Vector3::new(0.0, -1.0, 0.0)),
+            (Point3::new(-0.6, 0.3, 1.0), Vector3::new(0.0, 0.0, 1.0)),
+            (Point3::new(0.4, 0.4, -1.0), Vector3::new(0.0, 0.0, -1.0)),
+            (Point3::new(1.0, 1.0, 1.0), Vector3::new(1.0, 0.0, 0.0)),
+            (Point3::new(-1.0, -1.0, -1.0), Vector3::new(-1.0, 0.0, 0.0)),
+        ];
+
+        for (point, normal) in cases.iter() {
+            assert_eq!(c.local_normal_at(point), *normal);
+        }
+    }
+
+    #[test]
+    fn intersect_transformed_cube() {
+        let mut c = Cube::new();
+        c.set_transform(translation(0.0, 1.0, 0.0) * scaling(2.0, 2.0, 2.0));
+        let r = Ray::new(Point3::new(-5.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        let xs = c.intersect(&r);
+
+        assert_eq!(xs.len(), 2);
+        assert!(floats_equal(xs[0].t, 3.0));
+        assert!(floats_equal(xs[1].t, 7.0));
+    }
+
+    #[test]
+    fn normal_on_rotated_cube() {
+        let mut c = Cube::new();
+        c.set_transform(rotation_y(PI / 4.0));
+        let n = c.normal_at(&Point3::new(0.0, 0.0, -SQRT_2));
+
+        assert_eq!(n, Vector3::new(-FRAC_1_SQRT_2, 0.0, -FRAC_1_SQRT_2));
+    }
+}
@@ -0,0 +1,262 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::Intersection;
+use crate::material::Material;
+use crate::point::Point3;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeTransforms};
+use crate::utils::EPSILON;
+use crate::vector::Vector3;
+
+// Cylinder of radius 1 around the y axis, optionally truncated to minimum..maximum and capped when closed
+#[derive(Debug, PartialEq)]
+pub struct Cylinder {
+    transforms: ShapeTransforms,
+    pub material: Material,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Cylinder {
+    pub fn new() -> Self {
+        Cylinder {
+            transforms: ShapeTransforms::new(),
+            material: Material::new(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+
+    // Whether the intersection at t is within the radius of 1 from the y axis
+    fn check_cap(ray: &Ray, t: f64) -> bool {
+        let x = ray.origin.x + t * ray.direction.x;
+        let z = ray.origin.z + t * ray.direction.z;
+        (x * x + z * z) <= 1.0
+    }
+
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        if !self.closed || ray.direction.y.abs() < EPSILON {
+            return;
+        }
+
+        // Bounds at infinity don't have caps
+        for bound in [self.minimum, self.maximum].iter().filter(|bound| bound.is_finite()) {
+            let t = (bound - ray.origin.y) / ray.direction.y;
+            if Cylinder::check_cap(ray, t) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+}
+
+impl Shape for Cylinder {
+    fn transforms(&self) -> &ShapeTransforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut ShapeTransforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let mut xs = Vec::new();
+        let a = ray.direction.x * ray.direction.x + ray.direction.z * ray.direction.z;
+
+        // Rays parallel to the y axis can only hit the caps
+        if a.abs() >= EPSILON {
+            let b = 2.0 * ray.origin.x * ray.direction.x + 2.0 * ray.origin.z * ray.direction.z;
+            let c = ray.origin.x * ray.origin.x + ray.origin.z * ray.origin.z - 1.0;
+            let discriminant = (b * b) - (4.0 * a * c);
+
+            if discriminant < 0.0 {
+                // No hits
+                return xs;
+            }
+
+            let mut t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+            let mut t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            for t in [t0, t1] {
+                let y = ray.origin.y + t * ray.direction.y;
+                if self.minimum < y && y < self.maximum {
+                    xs.push(Intersection::new(t, self));
+                }
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+
+        xs
+    }
+
+    fn local_normal_at(&self, point: &Point3) -> Vector3 {
+        let dist = point.x * point.x + point.z * point.z;
+
+        if dist < 1.0 && point.y >= self.maximum - EPSILON {
+            Vector3::new(0.0, 1.0, 0.0)
+        } else if dist < 1.0 && point.y <= self.minimum + EPSILON {
+            Vector3::new(0.0, -1.0, 0.0)
+        } else {
+            Vector3::new(point.x, 0.0, point.z)
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(Point3::new(-1.0, self.minimum, -1.0), Point3::new(1.0, self.maximum, 1.0))
+    }
+}
+
+impl Default for Cylinder {
+    fn default() -> Self {
+        Cylinder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utils::floats_equal;
+
+    #[test]
+    fn ray_misses_cylinder() {
+        let cyl = Cylinder::new();
+        let cases = [
+            (Point3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Point3::new(0.0, 0.0, -5.0), Vector3::new(1.0, 1.0, 1.0)),
+        ];
+
+        for (origin, direction) in cases.iter() {
+            let r = Ray::new(*origin, direction.normalized());
+            let xs = cyl.local_intersect(&r);
+
+            assert_eq!(xs.len(), 0);
+        }
+    }
+
+    #[test]
+    fn ray_strikes_cylinder() {
+        let cyl = Cylinder::new();
+        let cases = [
+            (Point3::new(1.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 5.0, 5.0),
+            (Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 4.0, 6.0),
+            (Point3::new(0.5, 0.0, -5.0), Vector3::new(0.1, 1.0, 1.0), 6.80798, 7.08872),
+        ];
+
+        for (origin, direction, t0, t1) in cases.iter() {
+            let r = Ray::new(*origin, direction.normalized());
+            let xs = cyl.local_intersect(&r);
+
+            assert_eq!(xs.len(), 2);
+            assert!(floats_equal(xs[0].t, *t0));
+            assert!(floats_equal(xs[1].t, *t1));
+        }
+    }
+
+    #[test]
+    fn normal_vector_on_cylinder() {
+        let cyl = Cylinder::new();
+        let cases = [
+            (Point3::new(1.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0)),
+            (Point3::new(0.0, 5.0, -1.0), Vector3::new(0.0, 0.0, -1.0)),
+            (Point3::new(0.0, -2.0, 1.0), Vector3::new(0.0, 0.0, 1.0)),
+            (Point3::new(-1.0, 1.0, 0.0), Vector3::new(-1.0, 0.0, 0.0)),
+        ];
+
+        for (point, normal) in cases.iter() {
+            assert_eq!(cyl.local_normal_at(point), *normal);
+        }
+    }
+
+    #[test]
+    fn default_minimum_and_maximum_for_cylinder() {
+        let cyl = Cylinder::new();
+
+        assert_eq!(cyl.minimum, f64::NEG_INFINITY);
+        assert_eq!(cyl.maximum, f64::INFINITY);
+    }
+
+    #[test]
+    fn intersecting_constrained_cylinder() {
+        let mut cyl = Cylinder::new();
+        cyl.minimum = 1.0;
+        cyl.maximum = 2.0;
+        let cases = [
+            (Point3::new(0.0, 1.5, 0.0), Vector3::new(0.1, 1.0, 0.0), 0),
+            (Point3::new(0.0, 3.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+            (Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+            (Point3::new(0.0, 2.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+            (Point3::new(0.0, 1.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+            (Point3::new(0.0, 1.5, -2.0), Vector3::new(0.0, 0.0, 1.0), 2),
+        ];
+
+        for (point, direction, count) in cases.iter() {
+            let r = Ray::new(*point, direction.normalized());
+            let xs = cyl.local_intersect(&r);
+
+            assert_eq!(xs.len(), *count);
+        }
+    }
+
+    #[test]
+    fn default_closed_value_for_cylinder() {
+        let cyl = Cylinder::new();
+
+        assert!(!cyl.closed);
+    }
+
+    #[test]
+    fn intersecting_caps_of_closed_cylinder() {
+        let mut cyl = Cylinder::new();
+        cyl.minimum = 1.0;
+        cyl.maximum = 2.0;
+        cyl.closed = true;
+        let cases = [
+            (Point3::new(0.0, 3.0, 0.0), Vector3::new(0.0, -1.0, 0.0), 2),
+            (Point3::new(0.0, 3.0, -2.0), Vector3::new(0.0, -1.0, 2.0), 2),
+            (Point3::new(0.0, 4.0, -2.0), Vector3::new(0.0, -1.0, 1.0), 2),
+            (Point3::new(0.0, 0.0, -2.0), Vector3::new(0.0, 1.0, 2.0), 2),
+            (Point3::new(0.0, -1.0, -2.0), Vector3::new(0.0, 1.0, 1.0), 2),
+        ];
+
+        for (point, direction, count) in cases.iter() {
+            let r = Ray::new(*point, direction.normalized());
+            let xs = cyl.local_intersect(&r);
+
+            assert_eq!(xs.len(), *count);
+        }
+    }
+
+    #[test]
+    fn normal_vector_on_cylinder_end_caps() {
+        let mut cyl = Cylinder::new();
+        cyl.minimum = 1.0;
+        cyl.maximum = 2.0;
+        cyl.closed = true;
+        let cases = [
+            (Point3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+            (Point3::new(0.5, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+            (Point3::new(0.0, 1.0, 0.5), Vector3::new(0.0, -1.0, 0.0)),
+            (Point3::new(0.0, 2.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Point3::new(0.5, 2.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Point3::new(0.0, 2.0, 0.5), Vector3::new(0.0, 1.0, 0.0)),
+        ];
+
+        for (point, normal) in cases.iter() {
+            assert_eq!(cyl.local_normal_at(point), *normal);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use std::cmp::Ordering;
+
+use crate::bounds::BoundingBox;
+use crate::bvh::{Bvh, BvhOptions, BvhStats};
+use crate::intersection::Intersection;
+use crate::material::Material;
+use crate::matrix::Matrix4;
+use crate::point::Point3;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeTransforms};
+use crate::vector::Vector3;
+
+// A collection of shapes that are transformed together. The group's transform is pushed down to its children
+// whenever it changes, so children can convert between world and object space without a reference to their parent.
+// Once `build_bvh` is called, rays are only tested against the children whose bounds they pass through.
+#[derive(Debug, PartialEq)]
+pub struct Group {
+    transforms: ShapeTransforms,
+    pub material: Material,
+    children: Vec<Box<dyn Shape>>,
+    bvh: Option<Bvh>,
+}
+
+impl Group {
+    pub fn new() -> Self {
+        Group {
+            transforms: ShapeTransforms::new(),
+            material: Material::new(),
+            children: Vec::new(),
+            bvh: None,
+        }
+    }
+
+    pub fn children(&self) -> &[Box<dyn Shape>] {
+        &self.children
+    }
+
+    pub fn add_child(&mut self, mut child: Box<dyn Shape>) {
+        child.set_parent_transform(*self.world_transform());
+        self.children.push(child);
+        // The hierarchy no longer covers every child
+        self.bvh = None;
+    }
+
+    // None until `build_bvh` is called, or after children are added
+    pub fn bvh_stats(&self) -> Option<BvhStats> {
+        self.bvh.as_ref().map(|bvh| bvh.stats())
+    }
+
+    fn update_children(&mut self) {
+        let world_transform = *self.world_transform();
+
+        for child in self.children.iter_mut() {
+            child.set_parent_transform(world_transform);
+        }
+    }
+}
+
+impl Shape for Group {
+    fn transforms(&self) -> &ShapeTransforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut ShapeTransforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transforms.set_local(transform);
+        self.update_children();
+    }
+
+    fn set_parent_transform(&mut self, transform: Matrix4) {
+        self.transforms.set_parent(transform);
+        self.update_children();
+    }
+
+    fn build_bvh(&mut self, options: &BvhOptions) {
+        for child in self.children.iter_mut() {
+            child.build_bvh(options);
+        }
+
+        let bounds: Vec<BoundingBox> = self.children.iter().map(|child| child.parent_space_bounds()).collect();
+        self.bvh = Some(Bvh::build(&bounds, options));
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.children.iter().any(|child| child.includes(other))
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let mut xs: Vec<Intersection> = match &self.bvh {
+            Some(bvh) => {
+                let mut xs = Vec::new();
+                bvh.traverse(ray, |i| xs.extend(self.children[i].intersect(ray)));
+                xs
+            }
+            None => self.children.iter().flat_map(|child| child.intersect(ray)).collect(),
+        };
+        xs.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap_or(Ordering::Equal));
+        xs
+    }
+
+    fn local_normal_at(&self, _point: &Point3) -> Vector3 {
+        // Intersections always report the child that was hit, never the group itself
+        panic!("Groups have no normals of their own, use the normal of the child that was hit");
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+
+        for child in &self.children {
+            bounds.merge(&child.parent_space_bounds());
+        }
+
+        bounds
+    }
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Group::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::{
+        bvh::MAX_LEAF_SIZE,
+        cylinder::Cylinder,
+        shape::same_shape,
+        sphere::Sphere,
+        transformation::{rotation_y, scaling, translation},
+    };
+
+    fn as_group(shape: &dyn Shape) -> &Group {
+        shape.as_any().downcast_ref::<Group>().unwrap()
+    }
+
+    #[test]
+    fn creating_new_group() {
+        let g = Group::new();
+
+        assert_eq!(*g.transform(), Matrix4::identity());
+        assert!(g.children().is_empty());
+    }
+
+    #[test]
+    fn adding_child_to_group() {
+        let mut g = Group::new();
+        g.set_transform(translation(1.0, 2.0, 3.0));
+        g.add_child(Box::new(Sphere::new()));
+
+        assert_eq!(g.children().len(), 1);
+        assert!(g.children()[0].as_any().is::<Sphere>());
+        assert_eq!(*g.children()[0].parent_transform(), translation(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn changing_group_transform_updates_children() {
+        let mut g = Group::new();
+        g.add_child(Box::new(Sphere::new()));
+        g.set_transform(scaling(2.0, 2.0, 2.0));
+
+        assert_eq!(*g.children()[0].parent_transform(), scaling(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn intersecting_ray_with_empty_group() {
+        let g = Group::new();
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = g.local_intersect(&r);
+
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn intersecting_ray_with_nonempty_group() {
+        let mut g = Group::new();
+        let s1 = Sphere::new();
+        let mut s2 = Sphere::new();
+        s2.set_transform(translation(0.0, 0.0, -3.0));
+        let mut s3 = Sphere::new();
+        s3.set_transform(translation(5.0, 0.0, 0.0));
+        g.add_child(Box::new(s1));
+        g.add_child(Box::new(s2));
+        g.add_child(Box::new(s3));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = g.local_intersect(&r);
+
+        assert_eq!(xs.len(), 4);
+        assert!(same_shape(xs[0].object, g.children()[1].as_ref()));
+        assert!(same_shape(xs[1].object, g.children()[1].as_ref()));
+        assert!(same_shape(xs[2].object, g.children()[0].as_ref()));
+        assert!(same_shape(xs[3].object, g.children()[0].as_ref()));
+    }
+
+    #[test]
+    fn intersecting_transformed_group() {
+        let mut g = Group::new();
+        g.set_transform(scaling(2.0, 2.0, 2.0));
+        let mut s = Sphere::new();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        g.add_child(Box::new(s));
+        let r = Ray::new(Point3::new(10.0, 0.0, -10.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = g.intersect(&r);
+
+        assert_eq!(xs.len(), 2);
+    }
+
+    #[test]
+    fn converting_point_from_world_to_object_space() {
+        let mut g1 = Group::new();
+        g1.set_transform(rotation_y(PI / 2.0));
+        let mut g2 = Group::new();
+        g2.set_transform(scaling(2.0, 2.0, 2.0));
+        let mut s = Sphere::new();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        g2.add_child(Box::new(s));
+        g1.add_child(Box::new(g2));
+        let s = as_group(g1.children()[0].as_ref()).children()[0].as_ref();
+        let p = s.world_to_object(&Point3::new(-2.0, 0.0, -10.0));
+
+        assert_eq!(p, Point3::new(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn converting_normal_from_object_to_world_space() {
+        let mut g1 = Group::new();
+        g1.set_transform(rotation_y(PI / 2.0));
+        let mut g2 = Group::new();
+        g2.set_transform(scaling(1.0, 2.0, 3.0));
+        let mut s = Sphere::new();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        g2.add_child(Box::new(s));
+        g1.add_child(Box::new(g2));
+        let s = as_group(g1.children()[0].as_ref()).children()[0].as_ref();
+        let n = s.normal_to_world(&Vector3::new(f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0));
+
+        assert_eq!(n, Vector3::new(0.28571, 0.42857, -0.85714));
+    }
+
+    #[test]
+    fn finding_normal_on_child_object() {
+        let mut g1 = Group::new();
+        g1.set_transform(rotation_y(PI / 2.0));
+        let mut g2 = Group::new();
+        g2.set_transform(scaling(1.0, 2.0, 3.0));
+        let mut s = Sphere::new();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        g2.add_child(Box::new(s));
+        g1.add_child(Box::new(g2));
+        let s = as_group(g1.children()[0].as_ref()).children()[0].as_ref();
+        let n = s.normal_at(&Point3::new(1.7321, 1.1547, -5.5774));
+
+        assert_eq!(n, Vector3::new(0.2857, 0.42854, -0.85716));
+    }
+
+    #[test]
+    fn setting_transform_after_nesting_reaches_grandchildren() {
+        let mut g1 = Group::new();
+        let mut g2 = Group::new();
+        g2.set_transform(scaling(2.0, 2.0, 2.0));
+        g2.add_child(Box::new(Sphere::new()));
+        g1.add_child(Box::new(g2));
+        g1.set_transform(translation(0.0, 1.0, 0.0));
+        let s = as_group(g1.children()[0].as_ref()).children()[0].as_ref();
+
+        assert_eq!(*s.world_transform(), translation(0.0, 1.0, 0.0) * scaling(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn group_has_bounding_box_that_contains_its_children() {
+        let mut s = Sphere::new();
+        s.set_transform(translation(2.0, 5.0, -3.0) * scaling(2.0, 2.0, 2.0));
+        let mut c = Cylinder::new();
+        c.minimum = -2.0;
+        c.maximum = 2.0;
+        c.set_transform(translation(-4.0, -1.0, 4.0) * scaling(0.5, 1.0, 0.5));
+        let mut g = Group::new();
+        g.add_child(Box::new(s));
+        g.add_child(Box::new(c));
+        let b = g.bounds();
+
+        assert_eq!(b.min, Point3::new(-4.5, -3.0, -5.0));
+        assert_eq!(b.max, Point3::new(4.0, 7.0, 4.5));
+    }
+
+    #[test]
+    fn intersecting_group_with_bvh_matches_naive_intersection() {
+        let mut g = Group::new();
+        for i in 0..20 {
+            let mut s = Sphere::new();
+            s.set_transform(translation(i as f64 * 3.0 - 30.0, 0.0, (i % 3) as f64));
+            g.add_child(Box::new(s));
+        }
+        let r = Ray::new(Point3::new(-30.0, 0.0, -5.0), Vector3::new(1.0, 0.0, 0.2).normalized());
+        let naive: Vec<f64> = g.local_intersect(&r).iter().map(|i| i.t).collect();
+        g.build_bvh(&BvhOptions::new());
+        let median: Vec<f64> = g.local_intersect(&r).iter().map(|i| i.t).collect();
+        g.build_bvh(&BvhOptions::sah());
+        let sah: Vec<f64> = g.local_intersect(&r).iter().map(|i| i.t).collect();
+
+        assert!(!naive.is_empty());
+        assert_eq!(naive, median);
+        assert_eq!(naive, sah);
+        assert!(g.bvh_stats().unwrap().average_leaf_size <= MAX_LEAF_SIZE as f64);
+    }
+
+    #[test]
+    fn adding_child_discards_bvh() {
+        let mut g = Group::new();
+        g.build_bvh(&BvhOptions::new());
+        g.add_child(Box::new(Sphere::new()));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert!(g.bvh_stats().is_none());
+        assert_eq!(g.local_intersect(&r).len(), 2);
+    }
+}
@@ -1,33 +1,52 @@
 use std::cmp::Ordering;
 
-use crate::{ray::Ray, sphere::Sphere, tuple::Tuple};
+use crate::{
+    point::Point3,
+    ray::Ray,
+    shape::{same_shape, Shape},
+    utils::EPSILON,
+    vector::Vector3,
+};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Intersection<'a> {
     pub t: f64,
-    pub object: &'a Sphere,
+    pub object: &'a dyn Shape,
+    // Where on a triangle the hit landed, zero for other shapes
+    pub u: f64,
+    pub v: f64,
 }
 
 pub struct Computations<'a> {
     pub t: f64,
-    pub object: &'a Sphere,
-    pub point: Tuple,
-    pub eyev: Tuple,
-    pub normalv: Tuple,
+    pub object: &'a dyn Shape,
+    pub point: Point3,
+    pub over_point: Point3,
+    pub under_point: Point3,
+    pub eyev: Vector3,
+    pub normalv: Vector3,
+    pub reflectv: Vector3,
     pub inside: bool,
+    pub n1: f64,
+    pub n2: f64,
 }
 
 impl<'a> Intersection<'a> {
-    pub fn new(t: f64, object: &'a Sphere) -> Intersection<'a> {
-        Intersection { t, object }
+    pub fn new(t: f64, object: &'a dyn Shape) -> Intersection<'a> {
+        Intersection { t, object, u: 0.0, v: 0.0 }
     }
 
-    pub fn prepare_computations(&self, ray: &Ray) -> Computations {
+    pub fn with_uv(t: f64, object: &'a dyn Shape, u: f64, v: f64) -> Intersection<'a> {
+        Intersection { t, object, u, v }
+    }
+
+    // xs is the full list of intersections the hit came from, used to find the refractive indices on either side of it
+    pub fn prepare_computations(&self, ray: &Ray, xs: &[Intersection<'a>]) -> Computations<'a> {
         let t = self.t;
         let object = self.object;
         let point = ray.position(self.t);
         let eyev = -ray.direction;
-        let mut normalv = self.object.normal_at(&ray.position(self.t));
+        let mut normalv = self.object.normal_at_hit(&point, self);
         let mut inside = false;
 
         if normalv.dot(&eyev).is_sign_negative() {
@@ -35,14 +54,86 @@ impl<'a> Intersection<'a> {
             normalv = -normalv;
         }
 
+        let reflectv = ray.direction.reflect(&normalv);
+
+        // Nudge the point slightly above the surface so shadow rays don't hit the object itself
+        let over_point = point + normalv * EPSILON;
+        let under_point = point - normalv * EPSILON;
+
+        let (n1, n2) = self.refractive_indices(xs);
+
         Computations {
             t,
             object,
             point,
+            over_point,
+            under_point,
             eyev,
             normalv,
+            reflectv,
             inside,
+            n1,
+            n2,
+        }
+    }
+
+    fn is_same(&self, other: &Intersection) -> bool {
+        self.t == other.t && same_shape(self.object, other.object)
+    }
+
+    // Walk the intersections up to this one, tracking which objects the ray is currently inside of
+    fn refractive_indices(&self, xs: &[Intersection<'a>]) -> (f64, f64) {
+        let mut containers: Vec<&dyn Shape> = Vec::new();
+        let mut n1 = 1.0;
+        let mut n2 = 1.0;
+
+        for i in xs {
+            let is_hit = self.is_same(i);
+
+            if is_hit {
+                if let Some(last) = containers.last() {
+                    n1 = last.material().refractive_index;
+                }
+            }
+
+            match containers.iter().position(|o| same_shape(*o, i.object)) {
+                Some(index) => {
+                    containers.remove(index);
+                }
+                None => containers.push(i.object),
+            }
+
+            if is_hit {
+                if let Some(last) = containers.last() {
+                    n2 = last.material().refractive_index;
+                }
+                break;
+            }
         }
+
+        (n1, n2)
+    }
+}
+
+impl<'a> Computations<'a> {
+    // Schlick's approximation of the Fresnel reflectance
+    pub fn schlick(&self) -> f64 {
+        let mut cos = self.eyev.dot(&self.normalv);
+
+        if self.n1 > self.n2 {
+            let n = self.n1 / self.n2;
+            let sin2_t = n.powi(2) * (1.0 - cos.powi(2));
+
+            // Total internal reflection
+            if sin2_t > 1.0 {
+                return 1.0;
+            }
+
+            cos = (1.0 - sin2_t).sqrt();
+        }
+
+        let r0 = ((self.n1 - self.n2) / (self.n1 + self.n2)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cos).powi(5)
     }
 }
 
@@ -53,19 +144,20 @@ pub fn intersections<'a>(xs: &[Intersection<'a>]) -> Vec<Intersection<'a>> {
 }
 
 pub fn hit<'a>(xs: &'a [Intersection]) -> Option<&'a Intersection<'a>> {
-    for x in xs {
-        if x.t.is_sign_positive() {
-            return Some(x);
-        }
-    }
-
-    None
+    xs.iter().find(|x| x.t.is_sign_positive())
 }
 
 #[cfg(test)]
 mod tests {
+    use std::f64::consts::SQRT_2;
+
     use super::*;
-    use crate::utils::floats_equal;
+    use crate::{
+        plane::Plane,
+        sphere::Sphere,
+        transformation::{scaling, translation},
+        utils::floats_equal,
+    };
 
     #[test]
     fn intersection_encapsulates_t_and_object() {
@@ -73,7 +165,16 @@ mod tests {
         let i = Intersection::new(3.5, &s);
 
         assert!(floats_equal(i.t, 3.5));
-        assert_eq!(i.object, &s);
+        assert_eq!(i.object, &s as &dyn Shape);
+    }
+
+    #[test]
+    fn intersection_can_encapsulate_u_and_v() {
+        let s = Sphere::new();
+        let i = Intersection::with_uv(3.5, &s, 0.2, 0.4);
+
+        assert!(floats_equal(i.u, 0.2));
+        assert!(floats_equal(i.v, 0.4));
     }
 
     #[test]
@@ -136,38 +237,133 @@ mod tests {
 
     #[test]
     fn precomputating_the_state_of_an_intersection() {
-        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
         let shape = Sphere::new();
         let i = Intersection::new(4.0, &shape);
-        let comps = i.prepare_computations(&r);
+        let comps = i.prepare_computations(&r, &[i]);
 
         assert!(floats_equal(comps.t, i.t));
         assert_eq!(comps.object, i.object);
-        assert_eq!(comps.point, Tuple::point(0.0, 0.0, -1.0));
-        assert_eq!(comps.eyev, Tuple::vector(0.0, 0.0, -1.0));
-        assert_eq!(comps.normalv, Tuple::vector(0.0, 0.0, -1.0));
+        assert_eq!(comps.point, Point3::new(0.0, 0.0, -1.0));
+        assert_eq!(comps.eyev, Vector3::new(0.0, 0.0, -1.0));
+        assert_eq!(comps.normalv, Vector3::new(0.0, 0.0, -1.0));
     }
 
     #[test]
     fn hit_when_an_intersection_occurs_on_the_outside() {
-        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
         let shape = Sphere::new();
         let i = Intersection::new(4.0, &shape);
-        let comps = i.prepare_computations(&r);
+        let comps = i.prepare_computations(&r, &[i]);
 
         assert!(!comps.inside);
     }
 
     #[test]
     fn hit_when_an_intersection_occurs_on_the_inside() {
-        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
         let shape = Sphere::new();
         let i = Intersection::new(1.0, &shape);
-        let comps = i.prepare_computations(&r);
+        let comps = i.prepare_computations(&r, &[i]);
 
-        assert_eq!(comps.point, Tuple::point(0.0, 0.0, 1.0));
-        assert_eq!(comps.eyev, Tuple::vector(0.0, 0.0, -1.0));
-        assert_eq!(comps.normalv, Tuple::vector(0.0, 0.0, -1.0));
+        assert_eq!(comps.point, Point3::new(0.0, 0.0, 1.0));
+        assert_eq!(comps.eyev, Vector3::new(0.0, 0.0, -1.0));
+        assert_eq!(comps.normalv, Vector3::new(0.0, 0.0, -1.0));
         assert!(comps.inside);
     }
+
+    #[test]
+    fn hit_should_offset_the_point() {
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let mut shape = Sphere::new();
+        shape.set_transform(translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+        let comps = i.prepare_computations(&r, &[i]);
+
+        assert!(comps.over_point.z < -EPSILON / 2.0);
+        assert!(comps.point.z > comps.over_point.z);
+    }
+
+    #[test]
+    fn precomputing_the_reflection_vector() {
+        let shape = Plane::new();
+        let r = Ray::new(Point3::new(0.0, 1.0, -1.0), Vector3::new(0.0, -SQRT_2 / 2.0, SQRT_2 / 2.0));
+        let i = Intersection::new(SQRT_2, &shape);
+        let comps = i.prepare_computations(&r, &[i]);
+
+        assert_eq!(comps.reflectv, Vector3::new(0.0, SQRT_2 / 2.0, SQRT_2 / 2.0));
+    }
+
+    #[test]
+    fn finding_n1_and_n2_at_various_intersections() {
+        let mut a = Sphere::glass();
+        a.set_transform(scaling(2.0, 2.0, 2.0));
+        a.material.refractive_index = 1.5;
+        let mut b = Sphere::glass();
+        b.set_transform(translation(0.0, 0.0, -0.25));
+        b.material.refractive_index = 2.0;
+        let mut c = Sphere::glass();
+        c.set_transform(translation(0.0, 0.0, 0.25));
+        c.material.refractive_index = 2.5;
+        let r = Ray::new(Point3::new(0.0, 0.0, -4.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = intersections(&[
+            Intersection::new(2.0, &a),
+            Intersection::new(2.75, &b),
+            Intersection::new(3.25, &c),
+            Intersection::new(4.75, &b),
+            Intersection::new(5.25, &c),
+            Intersection::new(6.0, &a),
+        ]);
+        let expected = [(1.0, 1.5), (1.5, 2.0), (2.0, 2.5), (2.5, 2.5), (2.5, 1.5), (1.5, 1.0)];
+
+        for (index, (n1, n2)) in expected.iter().enumerate() {
+            let comps = xs[index].prepare_computations(&r, &xs);
+
+            assert!(floats_equal(comps.n1, *n1));
+            assert!(floats_equal(comps.n2, *n2));
+        }
+    }
+
+    #[test]
+    fn under_point_is_offset_below_the_surface() {
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let mut shape = Sphere::glass();
+        shape.set_transform(translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+        let xs = intersections(&[i]);
+        let comps = i.prepare_computations(&r, &xs);
+
+        assert!(comps.under_point.z > EPSILON / 2.0);
+        assert!(comps.point.z < comps.under_point.z);
+    }
+
+    #[test]
+    fn schlick_approximation_under_total_internal_reflection() {
+        let shape = Sphere::glass();
+        let r = Ray::new(Point3::new(0.0, 0.0, SQRT_2 / 2.0), Vector3::new(0.0, 1.0, 0.0));
+        let xs = intersections(&[Intersection::new(-SQRT_2 / 2.0, &shape), Intersection::new(SQRT_2 / 2.0, &shape)]);
+        let comps = xs[1].prepare_computations(&r, &xs);
+
+        assert!(floats_equal(comps.schlick(), 1.0));
+    }
+
+    #[test]
+    fn schlick_approximation_with_a_perpendicular_viewing_angle() {
+        let shape = Sphere::glass();
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+        let xs = intersections(&[Intersection::new(-1.0, &shape), Intersection::new(1.0, &shape)]);
+        let comps = xs[1].prepare_computations(&r, &xs);
+
+        assert!(floats_equal(comps.schlick(), 0.04));
+    }
+
+    #[test]
+    fn schlick_approximation_with_small_angle_and_n2_greater_than_n1() {
+        let shape = Sphere::glass();
+        let r = Ray::new(Point3::new(0.0, 0.99, -2.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = intersections(&[Intersection::new(1.8589, &shape)]);
+        let comps = xs[0].prepare_computations(&r, &xs);
+
+        assert!((comps.schlick() - 0.48873).abs() < 0.0001);
+    }
 }
@@ -1,13 +1,30 @@
+pub mod background;
+pub mod bounds;
+pub mod bvh;
 pub mod camera;
 pub mod canvas;
 pub mod color;
+pub mod cone;
+pub mod csg;
+pub mod cube;
+pub mod cylinder;
+pub mod group;
 pub mod intersection;
 pub mod light;
 pub mod material;
 pub mod matrix;
+pub mod obj;
+pub mod pattern;
+pub mod plane;
+pub mod point;
 pub mod ray;
+pub mod sampling;
+pub mod shape;
+pub mod smooth_triangle;
 pub mod sphere;
 pub mod transformation;
+pub mod triangle;
 pub mod tuple;
 mod utils;
+pub mod vector;
 pub mod world;
@@ -1,34 +1,437 @@
-use crate::{color::Color, tuple::Tuple};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    color::Color,
+    point::Point3,
+    utils::{point_seed, EPSILON},
+    vector::Vector3,
+};
+
+// How a light dims with distance. Attenuated lights have their intensity at a distance of 1, so a scene lit
+// with them needs brighter lights the larger it's modelled.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Attenuation {
+    None,
+    InverseSquare,
+    // Divides the intensity by constant + linear * distance + quadratic * distance²
+    Polynomial { constant: f64, linear: f64, quadratic: f64 },
+}
+
+impl Attenuation {
+    pub fn factor(&self, distance: f64) -> f64 {
+        // Points on the light itself count as being EPSILON away, so they're brightly lit instead of infinitely
+        let distance = distance.max(EPSILON);
+
+        match self {
+            Attenuation::None => 1.0,
+            Attenuation::InverseSquare => 1.0 / (distance * distance),
+            Attenuation::Polynomial { constant, linear, quadratic } => 1.0 / (constant + linear * distance + quadratic * distance * distance),
+        }
+    }
+}
 
 #[derive(Debug, Copy, Clone)]
 pub struct PointLight {
-    pub position: Tuple,
+    pub position: Point3,
     pub intensity: Color,
+    pub attenuation: Attenuation,
 }
 
 impl PointLight {
-    pub fn new(position: Tuple, intensity: Color) -> Self {
-        PointLight { position, intensity }
+    pub fn new(position: Point3, intensity: Color) -> Self {
+        PointLight {
+            position,
+            intensity,
+            attenuation: Attenuation::None,
+        }
     }
 }
 
 impl PartialEq for PointLight {
     fn eq(&self, other: &Self) -> bool {
-        self.position == other.position && self.intensity == other.intensity
+        self.position == other.position && self.intensity == other.intensity && self.attenuation == other.attenuation
+    }
+}
+
+// Rectangular light spanned by two edges from a corner, split into a grid of cells that are each sampled once.
+// Points that only see some of the cells end up in a penumbra.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AreaLight {
+    pub corner: Point3,
+    pub u_edge: Vector3,
+    pub u_steps: usize,
+    pub v_edge: Vector3,
+    pub v_steps: usize,
+    pub intensity: Color,
+    // Applied to each sample separately, so the nearer parts of the light count for more
+    pub attenuation: Attenuation,
+    // Samples a random spot in each cell rather than its centre, trading banding in the penumbrae for noise
+    pub jitter: bool,
+    pub seed: u64,
+}
+
+impl AreaLight {
+    pub fn new(corner: Point3, u_edge: Vector3, u_steps: usize, v_edge: Vector3, v_steps: usize, intensity: Color) -> Self {
+        AreaLight {
+            corner,
+            u_edge,
+            u_steps,
+            v_edge,
+            v_steps,
+            intensity,
+            attenuation: Attenuation::None,
+            jitter: true,
+            seed: 0,
+        }
+    }
+
+    // Centre of the light
+    pub fn position(&self) -> Point3 {
+        self.corner + (self.u_edge + self.v_edge) * 0.5
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.u_steps.max(1) * self.v_steps.max(1)
+    }
+
+    // Point in cell (u, v), offset within the cell by a fraction between 0 and 1 along each edge
+    pub fn point_on_light(&self, u: usize, v: usize, offset: (f64, f64)) -> Point3 {
+        let (u_steps, v_steps) = (self.u_steps.max(1) as f64, self.v_steps.max(1) as f64);
+
+        self.corner + self.u_edge * ((u as f64 + offset.0) / u_steps) + self.v_edge * ((v as f64 + offset.1) / v_steps)
+    }
+
+    // One point per cell as seen from the shading point. Jitter is seeded by the shading point, so the lighting and the
+    // shadow test see the same points and renders stay reproducible.
+    pub fn samples(&self, point: Point3) -> Vec<Point3> {
+        let mut rng = StdRng::seed_from_u64(self.seed ^ point_seed(&point));
+        let mut samples = Vec::with_capacity(self.sample_count());
+
+        for v in 0..self.v_steps.max(1) {
+            for u in 0..self.u_steps.max(1) {
+                let offset = if self.jitter { (rng.gen::<f64>(), rng.gen::<f64>()) } else { (0.5, 0.5) };
+                samples.push(self.point_on_light(u, v, offset));
+            }
+        }
+
+        samples
+    }
+}
+
+// Cone of light from a position, fully lit within `angle - falloff` of its axis and smoothly fading out up to `angle`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SpotLight {
+    pub position: Point3,
+    pub direction: Vector3,
+    pub angle: f64,
+    pub falloff: f64,
+    pub intensity: Color,
+    pub attenuation: Attenuation,
+}
+
+impl SpotLight {
+    pub fn new(position: Point3, direction: Vector3, angle: f64, falloff: f64, intensity: Color) -> Self {
+        SpotLight {
+            position,
+            direction,
+            angle,
+            falloff,
+            intensity,
+            attenuation: Attenuation::None,
+        }
+    }
+
+    // How much of the intensity reaches the point, from 1 inside the inner cone to 0 outside the outer one
+    pub fn cone_factor(&self, point: Point3) -> f64 {
+        let cos_theta = (point - self.position).normalized().dot(&self.direction.normalized());
+        let cos_outer = self.angle.cos();
+        let cos_inner = (self.angle - self.falloff).max(0.0).cos();
+
+        if cos_theta >= cos_inner {
+            1.0
+        } else if cos_theta <= cos_outer {
+            0.0
+        } else {
+            let t = (cos_theta - cos_outer) / (cos_inner - cos_outer);
+            t * t * (3.0 - 2.0 * t)
+        }
+    }
+}
+
+// Light from infinitely far away (like the sun), arriving everywhere from the same direction
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DirectionalLight {
+    // The direction the light travels in
+    pub direction: Vector3,
+    pub intensity: Color,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Vector3, intensity: Color) -> Self {
+        DirectionalLight { direction, intensity }
+    }
+}
+
+// A light as seen from a shading point
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LightSample {
+    // Normalized, pointing from the shading point towards the light
+    pub direction: Vector3,
+    // How far a shadow ray has to go before reaching the light, infinite for directional lights
+    pub distance: f64,
+    // Intensity arriving at the point
+    pub intensity: Color,
+}
+
+impl LightSample {
+    fn towards(position: Point3, point: Point3, intensity: Color, attenuation: Attenuation) -> Self {
+        let v = position - point;
+        let distance = v.magnitude();
+        // There's no direction to the light from a point on it, so it gets no diffuse or specular light from there
+        let direction = if distance > 0.0 { v.normalized() } else { Vector3::new(0.0, 0.0, 0.0) };
+
+        LightSample {
+            direction,
+            distance,
+            intensity: intensity * attenuation.factor(distance),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Light {
+    Point(PointLight),
+    Area(AreaLight),
+    Spot(SpotLight),
+    Directional(DirectionalLight),
+}
+
+impl Light {
+    pub fn intensity(&self) -> Color {
+        match self {
+            Light::Point(light) => light.intensity,
+            Light::Area(light) => light.intensity,
+            Light::Spot(light) => light.intensity,
+            Light::Directional(light) => light.intensity,
+        }
+    }
+
+    // What shading and shadows are averaged over at the point, one sample for every light but area lights
+    pub fn samples(&self, point: Point3) -> Vec<LightSample> {
+        match self {
+            Light::Point(light) => vec![LightSample::towards(light.position, point, light.intensity, light.attenuation)],
+            Light::Area(light) => light
+                .samples(point)
+                .into_iter()
+                .map(|position| LightSample::towards(position, point, light.intensity, light.attenuation))
+                .collect(),
+            Light::Spot(light) => vec![LightSample::towards(
+                light.position,
+                point,
+                light.intensity * light.cone_factor(point),
+                light.attenuation,
+            )],
+            Light::Directional(light) => vec![LightSample {
+                direction: -light.direction.normalized(),
+                distance: f64::INFINITY,
+                intensity: light.intensity,
+            }],
+        }
+    }
+}
+
+impl From<PointLight> for Light {
+    fn from(light: PointLight) -> Self {
+        Light::Point(light)
+    }
+}
+
+impl From<AreaLight> for Light {
+    fn from(light: AreaLight) -> Self {
+        Light::Area(light)
+    }
+}
+
+impl From<SpotLight> for Light {
+    fn from(light: SpotLight) -> Self {
+        Light::Spot(light)
+    }
+}
+
+impl From<DirectionalLight> for Light {
+    fn from(light: DirectionalLight) -> Self {
+        Light::Directional(light)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::f64::consts::PI;
+
     use super::*;
+    use crate::utils::floats_equal;
 
     #[test]
     fn point_light_has_position_and_intensity() {
-        let position = Tuple::point(0.0, 0.0, 0.0);
+        let position = Point3::origin();
         let intensity = Color::new(1.0, 1.0, 1.0);
         let light = PointLight::new(position, intensity);
 
         assert_eq!(light.position, position);
         assert_eq!(light.intensity, intensity);
     }
+
+    #[test]
+    fn creating_an_area_light() {
+        let light = AreaLight::new(
+            Point3::origin(),
+            Vector3::new(2.0, 0.0, 0.0),
+            4,
+            Vector3::new(0.0, 0.0, 1.0),
+            2,
+            Color::white(),
+        );
+
+        assert_eq!(light.sample_count(), 8);
+        assert_eq!(light.position(), Point3::new(1.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn finding_a_single_point_on_an_area_light() {
+        let light = AreaLight::new(
+            Point3::origin(),
+            Vector3::new(2.0, 0.0, 0.0),
+            4,
+            Vector3::new(0.0, 0.0, 1.0),
+            2,
+            Color::white(),
+        );
+        let cases = [
+            (0, 0, Point3::new(0.25, 0.0, 0.25)),
+            (1, 0, Point3::new(0.75, 0.0, 0.25)),
+            (0, 1, Point3::new(0.25, 0.0, 0.75)),
+            (2, 0, Point3::new(1.25, 0.0, 0.25)),
+            (3, 1, Point3::new(1.75, 0.0, 0.75)),
+        ];
+
+        for (u, v, expected) in cases.iter() {
+            assert_eq!(light.point_on_light(*u, *v, (0.5, 0.5)), *expected);
+        }
+    }
+
+    #[test]
+    fn jittered_samples_stay_within_their_cells() {
+        let light = AreaLight::new(
+            Point3::origin(),
+            Vector3::new(2.0, 0.0, 0.0),
+            4,
+            Vector3::new(0.0, 0.0, 1.0),
+            2,
+            Color::white(),
+        );
+        let point = Point3::new(0.3, -2.0, 0.7);
+        let samples = light.samples(point);
+
+        assert_eq!(samples.len(), 8);
+        for (i, sample) in samples.iter().enumerate() {
+            let (u, v) = ((i % 4) as f64, (i / 4) as f64);
+
+            assert!(sample.x >= u * 0.5 && sample.x <= (u + 1.0) * 0.5);
+            assert!(sample.z >= v * 0.5 && sample.z <= (v + 1.0) * 0.5);
+        }
+        // The same point always sees the same samples
+        assert_eq!(samples, light.samples(point));
+    }
+
+    #[test]
+    fn point_light_sample_points_at_the_light() {
+        let light = Light::from(PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::white()));
+        let samples = light.samples(Point3::new(0.0, 0.0, 0.0));
+
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].direction, Vector3::new(0.0, 0.0, -1.0));
+        assert!(floats_equal(samples[0].distance, 10.0));
+        assert_eq!(samples[0].intensity, Color::white());
+    }
+
+    #[test]
+    fn spot_light_fades_out_towards_the_edge_of_its_cone() {
+        let light = SpotLight::new(
+            Point3::new(0.0, 10.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            PI / 4.0,
+            PI / 8.0,
+            Color::white(),
+        );
+        let inside = light.cone_factor(Point3::new(1.0, 0.0, 0.0));
+        let falloff = light.cone_factor(Point3::new(6.0, 0.0, 0.0));
+        let edge = light.cone_factor(Point3::new(8.0, 0.0, 0.0));
+        let outside = light.cone_factor(Point3::new(20.0, 0.0, 0.0));
+
+        assert!(floats_equal(inside, 1.0));
+        assert!(falloff > edge && edge > 0.0 && falloff < 1.0);
+        assert!(floats_equal(outside, 0.0));
+    }
+
+    #[test]
+    fn spot_light_sample_is_dimmed_by_its_cone() {
+        let light = Light::from(SpotLight::new(
+            Point3::new(0.0, 10.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            PI / 4.0,
+            0.0,
+            Color::white(),
+        ));
+
+        assert_eq!(light.samples(Point3::new(0.0, 0.0, 0.0))[0].intensity, Color::white());
+        assert_eq!(light.samples(Point3::new(20.0, 0.0, 0.0))[0].intensity, Color::black());
+    }
+
+    #[test]
+    fn directional_light_comes_from_infinitely_far_away() {
+        let light = Light::from(DirectionalLight::new(Vector3::new(0.0, -2.0, 0.0), Color::white()));
+
+        for point in [Point3::new(0.0, 0.0, 0.0), Point3::new(100.0, -50.0, 3.0)] {
+            let samples = light.samples(point);
+
+            assert_eq!(samples.len(), 1);
+            assert_eq!(samples[0].direction, Vector3::new(0.0, 1.0, 0.0));
+            assert!(samples[0].distance.is_infinite());
+        }
+    }
+
+    #[test]
+    fn attenuation_factors() {
+        let polynomial = Attenuation::Polynomial {
+            constant: 1.0,
+            linear: 0.5,
+            quadratic: 0.25,
+        };
+
+        assert!(floats_equal(Attenuation::None.factor(10.0), 1.0));
+        assert!(floats_equal(Attenuation::InverseSquare.factor(1.0), 1.0));
+        assert!(floats_equal(Attenuation::InverseSquare.factor(2.0), 0.25));
+        assert!(floats_equal(polynomial.factor(2.0), 1.0 / 3.0));
+    }
+
+    #[test]
+    fn attenuated_light_sample_dims_with_distance() {
+        let mut light = PointLight::new(Point3::new(0.0, 0.0, -2.0), Color::white());
+        light.attenuation = Attenuation::InverseSquare;
+        let sample = Light::from(light).samples(Point3::new(0.0, 0.0, 0.0))[0];
+
+        assert!(floats_equal(sample.distance, 2.0));
+        assert_eq!(sample.intensity, Color::new(0.25, 0.25, 0.25));
+    }
+
+    #[test]
+    fn attenuated_light_sample_at_the_light_stays_finite() {
+        let mut light = PointLight::new(Point3::new(1.0, 2.0, 3.0), Color::white());
+        light.attenuation = Attenuation::InverseSquare;
+        let sample = Light::from(light).samples(Point3::new(1.0, 2.0, 3.0))[0];
+
+        assert!(Attenuation::InverseSquare.factor(0.0).is_finite());
+        assert!(sample.intensity.r.is_finite());
+        assert_eq!(sample.direction, Vector3::new(0.0, 0.0, 0.0));
+    }
 }
@@ -1,61 +1,96 @@
-use crate::{color::Color, light::PointLight, tuple::Tuple, utils::floats_equal};
+use std::sync::Arc;
 
-#[derive(Debug, Copy, Clone)]
+use crate::{color::Color, light::Light, pattern::Pattern, point::Point3, shape::Shape, utils::floats_equal, vector::Vector3};
+
+#[derive(Debug, Clone)]
 pub struct Material {
     pub color: Color,
+    pub pattern: Option<Arc<dyn Pattern>>,
     pub ambient: f64,
     pub diffuse: f64,
     pub specular: f64,
     pub shininess: f64,
+    pub reflective: f64,
+    pub transparency: f64,
+    pub refractive_index: f64,
 }
 
 impl Material {
     pub fn new() -> Self {
         Material {
             color: Color::white(),
+            pattern: None,
             ambient: 0.1,
             diffuse: 0.9,
             specular: 0.9,
             shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
         }
     }
 
     pub fn from_params(color: Color, ambient: f64, diffuse: f64, specular: f64, shininess: f64) -> Self {
         Material {
             color,
+            pattern: None,
             ambient,
             diffuse,
             specular,
             shininess,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
+        }
+    }
+
+    // Color of the surface at the point, before any light falls on it
+    pub fn color_at(&self, object: &dyn Shape, point: Point3) -> Color {
+        match &self.pattern {
+            Some(pattern) => pattern.pattern_at_shape(object, &point),
+            None => self.color,
         }
     }
 
-    pub fn lighting(&self, light: PointLight, point: Tuple, eye: Tuple, normal: Tuple) -> Color {
-        let effective_color = self.color * light.intensity;
-        let lightv = (light.position - point).normalized();
-        let ambient = effective_color * self.ambient;
-        let light_dot_normal = lightv.dot(&normal);
+    // What's left of the surface with no light sources around
+    pub fn ambient_color(&self, object: &dyn Shape, point: Point3) -> Color {
+        self.color_at(object, point) * self.ambient
+    }
+
+    // Phong shading from a single light, with diffuse and specular averaged over the light's samples at the point.
+    // Visibility is the fraction of the light the point can see, 0 leaves only the ambient term.
+    pub fn lighting(&self, object: &dyn Shape, light: &Light, point: Point3, eye: Vector3, normal: Vector3, visibility: f64) -> Color {
+        let color = self.color_at(object, point);
+        let ambient = color * light.intensity() * self.ambient;
+
+        if visibility <= 0.0 {
+            return ambient;
+        }
+
+        let samples = light.samples(point);
+        let mut sum = Color::black();
+
+        for sample in samples.iter() {
+            let lightv = sample.direction;
+            let light_dot_normal = lightv.dot(&normal);
+
+            // The light is behind the surface
+            if light_dot_normal < 0.0 {
+                continue;
+            }
 
-        let diffuse;
-        let specular;
+            sum = sum + color * sample.intensity * self.diffuse * light_dot_normal;
 
-        if light_dot_normal < 0.0 {
-            diffuse = Color::black();
-            specular = Color::black();
-        } else {
-            diffuse = effective_color * self.diffuse * light_dot_normal;
             let reflectv = -lightv.reflect(&normal);
             let reflect_dot_eye = reflectv.dot(&eye);
 
-            if reflect_dot_eye <= 0.0 {
-                specular = Color::black();
-            } else {
+            if reflect_dot_eye > 0.0 {
                 let factor = reflect_dot_eye.powf(self.shininess);
-                specular = light.intensity * self.specular * factor;
+                sum = sum + sample.intensity * self.specular * factor;
             }
         }
 
-        ambient + diffuse + specular
+        ambient + sum * (visibility / samples.len() as f64)
     }
 }
 
@@ -71,15 +106,29 @@ impl PartialEq for Material {
             && floats_equal(self.diffuse, other.diffuse)
             && floats_equal(self.specular, other.specular)
             && floats_equal(self.shininess, other.shininess)
+            && floats_equal(self.reflective, other.reflective)
+            && floats_equal(self.transparency, other.transparency)
+            && floats_equal(self.refractive_index, other.refractive_index)
             && self.color == other.color
+            && match (&self.pattern, &other.pattern) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::f64::consts::SQRT_2;
+    use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
 
-    use crate::utils::floats_equal;
+    use crate::{
+        light::{AreaLight, Attenuation, PointLight},
+        pattern::StripePattern,
+        sphere::Sphere,
+        utils::floats_equal,
+        world::World,
+    };
 
     use super::*;
 
@@ -92,6 +141,9 @@ mod tests {
         assert!(floats_equal(m.diffuse, 0.9));
         assert!(floats_equal(m.specular, 0.9));
         assert!(floats_equal(m.shininess, 200.0));
+        assert!(floats_equal(m.reflective, 0.0));
+        assert!(floats_equal(m.transparency, 0.0));
+        assert!(floats_equal(m.refractive_index, 1.0));
     }
 
     #[test]
@@ -117,11 +169,11 @@ mod tests {
     #[test]
     fn lighting_with_the_eye_between_the_light_and_the_surface() {
         let m = Material::new();
-        let position = Tuple::point(0.0, 0.0, 0.0);
-        let eye = Tuple::vector(0.0, 0.0, -1.0);
-        let normal = Tuple::vector(0.0, 0.0, -1.0);
-        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::white());
-        let result = m.lighting(light, position, eye, normal);
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eye = Vector3::new(0.0, 0.0, -1.0);
+        let normal = Vector3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::white());
+        let result = m.lighting(&Sphere::new(), &light.into(), position, eye, normal, 1.0);
 
         assert_eq!(result, Color::new(1.9, 1.9, 1.9));
     }
@@ -129,11 +181,11 @@ mod tests {
     #[test]
     fn lighting_with_the_eye_between_light_surface_eye_offset_45() {
         let m = Material::new();
-        let position = Tuple::point(0.0, 0.0, 0.0);
-        let eye = Tuple::vector(0.0, SQRT_2 / 2.0, -SQRT_2 / 2.0);
-        let normal = Tuple::vector(0.0, 0.0, -1.0);
-        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::white());
-        let result = m.lighting(light, position, eye, normal);
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eye = Vector3::new(0.0, SQRT_2 / 2.0, -SQRT_2 / 2.0);
+        let normal = Vector3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::white());
+        let result = m.lighting(&Sphere::new(), &light.into(), position, eye, normal, 1.0);
 
         assert_eq!(result, Color::white());
     }
@@ -141,11 +193,11 @@ mod tests {
     #[test]
     fn lighting_with_eye_opposite_surface_light_offset_45() {
         let m = Material::new();
-        let position = Tuple::point(0.0, 0.0, 0.0);
-        let eye = Tuple::vector(0.0, 0.0, -1.0);
-        let normal = Tuple::vector(0.0, 0.0, -1.0);
-        let light = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Color::white());
-        let result = m.lighting(light, position, eye, normal);
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eye = Vector3::new(0.0, 0.0, -1.0);
+        let normal = Vector3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 10.0, -10.0), Color::white());
+        let result = m.lighting(&Sphere::new(), &light.into(), position, eye, normal, 1.0);
 
         assert_eq!(result, Color::new(0.7364, 0.7364, 0.7364));
     }
@@ -153,11 +205,11 @@ mod tests {
     #[test]
     fn lighting_with_eye_in_the_path_of_the_reflection_vector() {
         let m = Material::new();
-        let position = Tuple::point(0.0, 0.0, 0.0);
-        let eye = Tuple::vector(0.0, -SQRT_2 / 2.0, -SQRT_2 / 2.0);
-        let normal = Tuple::vector(0.0, 0.0, -1.0);
-        let light = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Color::white());
-        let result = m.lighting(light, position, eye, normal);
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eye = Vector3::new(0.0, -SQRT_2 / 2.0, -SQRT_2 / 2.0);
+        let normal = Vector3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 10.0, -10.0), Color::white());
+        let result = m.lighting(&Sphere::new(), &light.into(), position, eye, normal, 1.0);
 
         assert_eq!(result, Color::new(1.6364, 1.6364, 1.6364));
     }
@@ -165,11 +217,125 @@ mod tests {
     #[test]
     fn lighting_with_the_light_behind_the_surface() {
         let m = Material::new();
-        let position = Tuple::point(0.0, 0.0, 0.0);
-        let eye = Tuple::vector(0.0, 0.0, -1.0);
-        let normal = Tuple::vector(0.0, 0.0, -1.0);
-        let light = PointLight::new(Tuple::point(0.0, 0.0, 10.0), Color::white());
-        let result = m.lighting(light, position, eye, normal);
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eye = Vector3::new(0.0, 0.0, -1.0);
+        let normal = Vector3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 0.0, 10.0), Color::white());
+        let result = m.lighting(&Sphere::new(), &light.into(), position, eye, normal, 1.0);
+
+        assert_eq!(result, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn lighting_with_the_surface_in_shadow() {
+        let m = Material::new();
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eye = Vector3::new(0.0, 0.0, -1.0);
+        let normal = Vector3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::white());
+        let result = m.lighting(&Sphere::new(), &light.into(), position, eye, normal, 0.0);
+
+        assert_eq!(result, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn lighting_with_a_pattern_applied() {
+        let mut m = Material::new();
+        m.pattern = Some(Arc::new(StripePattern::new(Color::white(), Color::black())));
+        m.ambient = 1.0;
+        m.diffuse = 0.0;
+        m.specular = 0.0;
+        let eye = Vector3::new(0.0, 0.0, -1.0);
+        let normal = Vector3::new(0.0, 0.0, -1.0);
+        let light = PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::white());
+        let object = Sphere::new();
+        let c1 = m.lighting(&object, &light.into(), Point3::new(0.9, 0.0, 0.0), eye, normal, 1.0);
+        let c2 = m.lighting(&object, &light.into(), Point3::new(1.1, 0.0, 0.0), eye, normal, 1.0);
+
+        assert_eq!(c1, Color::white());
+        assert_eq!(c2, Color::black());
+    }
+
+    #[test]
+    fn lighting_uses_light_visibility_to_attenuate_color() {
+        let w = World::default();
+        let light: Light = PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::white()).into();
+        let shape = w.objects()[0].as_ref();
+        let mut m = shape.material().clone();
+        m.ambient = 0.1;
+        m.diffuse = 0.9;
+        m.specular = 0.0;
+        m.color = Color::white();
+        let point = Point3::new(0.0, 0.0, -1.0);
+        let eye = Vector3::new(0.0, 0.0, -1.0);
+        let normal = Vector3::new(0.0, 0.0, -1.0);
+        let cases = [(1.0, 1.0), (0.5, 0.55), (0.0, 0.1)];
+
+        for (visibility, expected) in cases.iter() {
+            let result = m.lighting(shape, &light, point, eye, normal, *visibility);
+            assert_eq!(result, Color::new(*expected, *expected, *expected));
+        }
+    }
+
+    #[test]
+    fn lighting_samples_the_area_light() {
+        let mut area = AreaLight::new(
+            Point3::new(-0.5, -0.5, -5.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            2,
+            Vector3::new(0.0, 1.0, 0.0),
+            2,
+            Color::white(),
+        );
+        area.jitter = false;
+        let light = Light::from(area);
+        let shape = Sphere::new();
+        let mut m = Material::new();
+        m.ambient = 0.1;
+        m.diffuse = 0.9;
+        m.specular = 0.0;
+        let eye = Point3::new(0.0, 0.0, -5.0);
+        let cases = [
+            (Point3::new(0.0, 0.0, -1.0), Color::new(0.9965, 0.9965, 0.9965)),
+            (Point3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2), Color::new(0.62318, 0.62318, 0.62318)),
+        ];
+
+        for (point, expected) in cases.iter() {
+            let eyev = (eye - *point).normalized();
+            let normal = Vector3::new(point.x, point.y, point.z);
+            let result = m.lighting(&shape, &light, *point, eyev, normal, 1.0);
+
+            assert_eq!(result, *expected);
+        }
+    }
+
+    #[test]
+    fn doubling_the_distance_to_an_attenuated_light_quarters_the_diffuse_term() {
+        let mut m = Material::new();
+        m.ambient = 0.0;
+        m.specular = 0.0;
+        let position = Point3::new(0.0, 0.0, 0.0);
+        let eye = Vector3::new(0.0, 0.0, -1.0);
+        let normal = Vector3::new(0.0, 0.0, -1.0);
+        let diffuse_at = |distance: f64| {
+            let mut light = PointLight::new(Point3::new(0.0, 0.0, -distance), Color::white());
+            light.attenuation = Attenuation::InverseSquare;
+            m.lighting(&Sphere::new(), &light.into(), position, eye, normal, 1.0)
+        };
+
+        assert_eq!(diffuse_at(1.0), Color::new(0.9, 0.9, 0.9));
+        assert_eq!(diffuse_at(2.0), diffuse_at(1.0) * 0.25);
+        assert_eq!(diffuse_at(4.0), diffuse_at(2.0) * 0.25);
+    }
+
+    #[test]
+    fn lighting_at_the_position_of_an_attenuated_light_leaves_only_ambient() {
+        let m = Material::new();
+        let mut light = PointLight::new(Point3::new(0.0, 0.0, 0.0), Color::white());
+        light.attenuation = Attenuation::InverseSquare;
+        let eye = Vector3::new(0.0, 0.0, -1.0);
+        let normal = Vector3::new(0.0, 0.0, -1.0);
+        let result = m.lighting(&Sphere::new(), &light.into(), Point3::new(0.0, 0.0, 0.0), eye, normal, 1.0);
 
         assert_eq!(result, Color::new(0.1, 0.1, 0.1));
     }
@@ -1,9 +1,13 @@
 use std::ops;
 
+use crate::point::Point3;
 use crate::tuple::Tuple;
 use crate::utils::floats_equal;
+use crate::vector::Vector3;
 
-#[derive(Debug)]
+// Square matrix of any size. Only needed for the submatrices that cofactors are built from,
+// everything else uses the fixed-size Matrix4.
+#[derive(Debug, Clone)]
 pub struct Matrix {
     pub dim: usize,
     pub elems: Vec<f64>,
@@ -14,29 +18,10 @@ impl Matrix {
         Matrix { dim, elems: elems.to_vec() }
     }
 
-    pub fn identity() -> Self {
-        Matrix {
-            dim: 4,
-            elems: vec![1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
-        }
-    }
-
     pub fn at(&self, row: usize, col: usize) -> f64 {
         self.elems[row * self.dim + col]
     }
 
-    pub fn transpose(&self) -> Self {
-        let mut elems: Vec<f64> = Vec::with_capacity(self.dim.pow(2));
-
-        for row in 0..self.dim {
-            for col in 0..self.dim {
-                elems.push(self.at(col, row));
-            }
-        }
-
-        Matrix::new(self.dim, &elems)
-    }
-
     pub fn det(&self) -> f64 {
         if self.dim == 2 {
             self.at(0, 0) * self.at(1, 1) - self.at(0, 1) * self.at(1, 0)
@@ -63,6 +48,8 @@ impl Matrix {
         self.submatrix(row, col).det()
     }
 
+    // Plain modulo rather than is_multiple_of, which is only stable since Rust 1.87
+    #[allow(clippy::manual_is_multiple_of)]
     pub fn cofactor(&self, row: usize, col: usize) -> f64 {
         if (row + col) % 2 == 0 {
             return self.minor(row, col);
@@ -70,25 +57,6 @@ impl Matrix {
 
         -self.minor(row, col)
     }
-
-    pub fn invertible(&self) -> bool {
-        !floats_equal(self.det(), 0.0)
-    }
-
-    pub fn inverse(&self) -> Self {
-        assert!(self.invertible());
-
-        let mut v: Vec<f64> = Vec::with_capacity(self.dim.pow(2));
-        let det = self.det();
-
-        for row in 0..self.dim {
-            for col in 0..self.dim {
-                v.push(self.cofactor(col, row) / det);
-            }
-        }
-
-        Matrix::new(self.dim, &v)
-    }
 }
 
 impl PartialEq for Matrix {
@@ -107,30 +75,140 @@ impl PartialEq for Matrix {
     }
 }
 
-impl ops::Mul for Matrix {
-    type Output = Matrix;
+// 4x4 matrix stored in row-major order on the stack, used for every transform
+#[derive(Debug, Clone, Copy)]
+pub struct Matrix4 {
+    pub elems: [f64; 16],
+}
 
-    fn mul(self, other: Matrix) -> Matrix {
-        assert!(self.dim == other.dim);
+impl Matrix4 {
+    pub fn new(elems: [f64; 16]) -> Self {
+        Matrix4 { elems }
+    }
 
-        let mut v: Vec<f64> = vec![0.0; self.dim.pow(2)];
+    pub fn identity() -> Self {
+        #[rustfmt::skip]
+        let elems = [
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ];
 
-        for row in 0..self.dim {
-            for col in 0..self.dim {
-                for x in 0..self.dim {
-                    v[row * self.dim + col] += self.at(row, x) * other.at(x, col);
-                }
+        Matrix4::new(elems)
+    }
+
+    pub fn at(&self, row: usize, col: usize) -> f64 {
+        self.elems[row * 4 + col]
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut elems = [0.0; 16];
+
+        for row in 0..4 {
+            for col in 0..4 {
+                elems[row * 4 + col] = self.at(col, row);
             }
         }
 
-        Matrix::new(self.dim, &v)
+        Matrix4::new(elems)
     }
-}
 
-impl ops::Mul<Tuple> for Matrix {
-    type Output = Tuple;
+    pub fn submatrix(&self, row: usize, col: usize) -> Matrix {
+        Matrix::new(4, &self.elems).submatrix(row, col)
+    }
 
-    fn mul(self, other: Tuple) -> Tuple {
+    pub fn minor(&self, row: usize, col: usize) -> f64 {
+        self.submatrix(row, col).det()
+    }
+
+    pub fn cofactor(&self, row: usize, col: usize) -> f64 {
+        Matrix::new(4, &self.elems).cofactor(row, col)
+    }
+
+    pub fn det(&self) -> f64 {
+        let (s, c) = self.sub_determinants();
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+
+    pub fn invertible(&self) -> bool {
+        !floats_equal(self.det(), 0.0)
+    }
+
+    // Closed-form inverse built from the 2x2 determinants of the top and bottom two rows, rather than
+    // sixteen cofactors each expanding a 3x3 submatrix
+    pub fn inverse(&self) -> Self {
+        let (s, c) = self.sub_determinants();
+        let det = s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0];
+        assert!(!floats_equal(det, 0.0));
+
+        let a = |row: usize, col: usize| self.at(row, col);
+        let inv_det = 1.0 / det;
+
+        #[rustfmt::skip]
+        let elems = [
+            (a(1, 1) * c[5] - a(1, 2) * c[4] + a(1, 3) * c[3]) * inv_det,
+            (-a(0, 1) * c[5] + a(0, 2) * c[4] - a(0, 3) * c[3]) * inv_det,
+            (a(3, 1) * s[5] - a(3, 2) * s[4] + a(3, 3) * s[3]) * inv_det,
+            (-a(2, 1) * s[5] + a(2, 2) * s[4] - a(2, 3) * s[3]) * inv_det,
+
+            (-a(1, 0) * c[5] + a(1, 2) * c[2] - a(1, 3) * c[1]) * inv_det,
+            (a(0, 0) * c[5] - a(0, 2) * c[2] + a(0, 3) * c[1]) * inv_det,
+            (-a(3, 0) * s[5] + a(3, 2) * s[2] - a(3, 3) * s[1]) * inv_det,
+            (a(2, 0) * s[5] - a(2, 2) * s[2] + a(2, 3) * s[1]) * inv_det,
+
+            (a(1, 0) * c[4] - a(1, 1) * c[2] + a(1, 3) * c[0]) * inv_det,
+            (-a(0, 0) * c[4] + a(0, 1) * c[2] - a(0, 3) * c[0]) * inv_det,
+            (a(3, 0) * s[4] - a(3, 1) * s[2] + a(3, 3) * s[0]) * inv_det,
+            (-a(2, 0) * s[4] + a(2, 1) * s[2] - a(2, 3) * s[0]) * inv_det,
+
+            (-a(1, 0) * c[3] + a(1, 1) * c[1] - a(1, 2) * c[0]) * inv_det,
+            (a(0, 0) * c[3] - a(0, 1) * c[1] + a(0, 2) * c[0]) * inv_det,
+            (-a(3, 0) * s[3] + a(3, 1) * s[1] - a(3, 2) * s[0]) * inv_det,
+            (a(2, 0) * s[3] - a(2, 1) * s[1] + a(2, 2) * s[0]) * inv_det,
+        ];
+
+        Matrix4::new(elems)
+    }
+
+    // 2x2 determinants of every pair of columns in the top two rows (s) and the bottom two rows (c)
+    fn sub_determinants(&self) -> ([f64; 6], [f64; 6]) {
+        let a = |row: usize, col: usize| self.at(row, col);
+        let pair = |r0: usize, r1: usize, c0: usize, c1: usize| a(r0, c0) * a(r1, c1) - a(r1, c0) * a(r0, c1);
+
+        let s = [
+            pair(0, 1, 0, 1),
+            pair(0, 1, 0, 2),
+            pair(0, 1, 0, 3),
+            pair(0, 1, 1, 2),
+            pair(0, 1, 1, 3),
+            pair(0, 1, 2, 3),
+        ];
+        let c = [
+            pair(2, 3, 0, 1),
+            pair(2, 3, 0, 2),
+            pair(2, 3, 0, 3),
+            pair(2, 3, 1, 2),
+            pair(2, 3, 1, 3),
+            pair(2, 3, 2, 3),
+        ];
+
+        (s, c)
+    }
+
+    fn mul_matrix(&self, other: &Matrix4) -> Matrix4 {
+        let mut elems = [0.0; 16];
+
+        for row in 0..4 {
+            for col in 0..4 {
+                elems[row * 4 + col] = (0..4).map(|x| self.at(row, x) * other.at(x, col)).sum();
+            }
+        }
+
+        Matrix4::new(elems)
+    }
+
+    fn mul_tuple(&self, other: &Tuple) -> Tuple {
         Tuple::new(
             self.at(0, 0) * other.x + self.at(0, 1) * other.y + self.at(0, 2) * other.z + self.at(0, 3) * other.w,
             self.at(1, 0) * other.x + self.at(1, 1) * other.y + self.at(1, 2) * other.z + self.at(1, 3) * other.w,
@@ -138,31 +216,114 @@ impl ops::Mul<Tuple> for Matrix {
             self.at(3, 0) * other.x + self.at(3, 1) * other.y + self.at(3, 2) * other.z + self.at(3, 3) * other.w,
         )
     }
+
+    // Points pick up the translation in the last column, vectors don't
+    fn mul_point(&self, other: &Point3) -> Point3 {
+        Point3::new(
+            self.at(0, 0) * other.x + self.at(0, 1) * other.y + self.at(0, 2) * other.z + self.at(0, 3),
+            self.at(1, 0) * other.x + self.at(1, 1) * other.y + self.at(1, 2) * other.z + self.at(1, 3),
+            self.at(2, 0) * other.x + self.at(2, 1) * other.y + self.at(2, 2) * other.z + self.at(2, 3),
+        )
+    }
+
+    fn mul_vector(&self, other: &Vector3) -> Vector3 {
+        Vector3::new(
+            self.at(0, 0) * other.x + self.at(0, 1) * other.y + self.at(0, 2) * other.z,
+            self.at(1, 0) * other.x + self.at(1, 1) * other.y + self.at(1, 2) * other.z,
+            self.at(2, 0) * other.x + self.at(2, 1) * other.y + self.at(2, 2) * other.z,
+        )
+    }
 }
 
-impl ops::Mul<Tuple> for &Matrix {
+impl Default for Matrix4 {
+    fn default() -> Self {
+        Matrix4::identity()
+    }
+}
+
+impl PartialEq for Matrix4 {
+    fn eq(&self, other: &Self) -> bool {
+        self.elems.iter().zip(&other.elems).all(|(a, b)| floats_equal(*a, *b))
+    }
+}
+
+impl ops::Mul for Matrix4 {
+    type Output = Matrix4;
+
+    fn mul(self, other: Matrix4) -> Matrix4 {
+        self.mul_matrix(&other)
+    }
+}
+
+impl ops::Mul<&Matrix4> for &Matrix4 {
+    type Output = Matrix4;
+
+    fn mul(self, other: &Matrix4) -> Matrix4 {
+        self.mul_matrix(other)
+    }
+}
+
+impl ops::Mul<Tuple> for Matrix4 {
     type Output = Tuple;
 
     fn mul(self, other: Tuple) -> Tuple {
-        Tuple::new(
-            self.at(0, 0) * other.x + self.at(0, 1) * other.y + self.at(0, 2) * other.z + self.at(0, 3) * other.w,
-            self.at(1, 0) * other.x + self.at(1, 1) * other.y + self.at(1, 2) * other.z + self.at(1, 3) * other.w,
-            self.at(2, 0) * other.x + self.at(2, 1) * other.y + self.at(2, 2) * other.z + self.at(2, 3) * other.w,
-            self.at(3, 0) * other.x + self.at(3, 1) * other.y + self.at(3, 2) * other.z + self.at(3, 3) * other.w,
-        )
+        self.mul_tuple(&other)
+    }
+}
+
+impl ops::Mul<Tuple> for &Matrix4 {
+    type Output = Tuple;
+
+    fn mul(self, other: Tuple) -> Tuple {
+        self.mul_tuple(&other)
     }
 }
 
-impl ops::Mul<&Tuple> for Matrix {
+impl ops::Mul<&Tuple> for Matrix4 {
     type Output = Tuple;
 
     fn mul(self, other: &Tuple) -> Tuple {
-        Tuple::new(
-            self.at(0, 0) * other.x + self.at(0, 1) * other.y + self.at(0, 2) * other.z + self.at(0, 3) * other.w,
-            self.at(1, 0) * other.x + self.at(1, 1) * other.y + self.at(1, 2) * other.z + self.at(1, 3) * other.w,
-            self.at(2, 0) * other.x + self.at(2, 1) * other.y + self.at(2, 2) * other.z + self.at(2, 3) * other.w,
-            self.at(3, 0) * other.x + self.at(3, 1) * other.y + self.at(3, 2) * other.z + self.at(3, 3) * other.w,
-        )
+        self.mul_tuple(other)
+    }
+}
+
+impl ops::Mul<&Tuple> for &Matrix4 {
+    type Output = Tuple;
+
+    fn mul(self, other: &Tuple) -> Tuple {
+        self.mul_tuple(other)
+    }
+}
+
+impl ops::Mul<Point3> for Matrix4 {
+    type Output = Point3;
+
+    fn mul(self, other: Point3) -> Point3 {
+        self.mul_point(&other)
+    }
+}
+
+impl ops::Mul<Point3> for &Matrix4 {
+    type Output = Point3;
+
+    fn mul(self, other: Point3) -> Point3 {
+        self.mul_point(&other)
+    }
+}
+
+impl ops::Mul<Vector3> for Matrix4 {
+    type Output = Vector3;
+
+    fn mul(self, other: Vector3) -> Vector3 {
+        self.mul_vector(&other)
+    }
+}
+
+impl ops::Mul<Vector3> for &Matrix4 {
+    type Output = Vector3;
+
+    fn mul(self, other: Vector3) -> Vector3 {
+        self.mul_vector(&other)
     }
 }
 
@@ -191,12 +352,7 @@ mod tests {
 
     #[test]
     fn construct_4_4_matrix() {
-        let matrix = Matrix::new(
-            4,
-            &[
-                1.0, 2.0, 3.0, 4.0, 5.5, 6.5, 7.5, 8.5, 9.0, 10.0, 11.0, 12.0, 13.5, 14.5, 15.5, 16.5, 69.2,
-            ],
-        );
+        let matrix = Matrix4::new([1.0, 2.0, 3.0, 4.0, 5.5, 6.5, 7.5, 8.5, 9.0, 10.0, 11.0, 12.0, 13.5, 14.5, 15.5, 16.5]);
 
         assert!(floats_equal(matrix.at(0, 0), 1.0));
         assert!(floats_equal(matrix.at(0, 3), 4.0));
@@ -209,37 +365,34 @@ mod tests {
 
     #[test]
     fn matrix_equality_with_identical_matrices() {
-        let matrix1 = Matrix::new(4, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0]);
-        let matrix2 = Matrix::new(4, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0]);
+        let matrix1 = Matrix4::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0]);
+        let matrix2 = Matrix4::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0]);
 
         assert!(matrix1 == matrix2);
     }
 
     #[test]
     fn matrix_equality_with_different_matrices() {
-        let matrix1 = Matrix::new(4, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0]);
-        let matrix2 = Matrix::new(4, &[2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0, 1.0]);
+        let matrix1 = Matrix4::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0]);
+        let matrix2 = Matrix4::new([2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0, 1.0]);
 
         assert!(matrix1 != matrix2);
     }
 
     #[test]
     fn multiply_matrices() {
-        let matrix1 = Matrix::new(4, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0]);
-        let matrix2 = Matrix::new(4, &[-2.0, 1.0, 2.0, 3.0, 3.0, 2.0, 1.0, -1.0, 4.0, 3.0, 6.0, 5.0, 1.0, 2.0, 7.0, 8.0]);
-        let expected_result = Matrix::new(
-            4,
-            &[
-                20.0, 22.0, 50.0, 48.0, 44.0, 54.0, 114.0, 108.0, 40.0, 58.0, 110.0, 102.0, 16.0, 26.0, 46.0, 42.0,
-            ],
-        );
+        let matrix1 = Matrix4::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0]);
+        let matrix2 = Matrix4::new([-2.0, 1.0, 2.0, 3.0, 3.0, 2.0, 1.0, -1.0, 4.0, 3.0, 6.0, 5.0, 1.0, 2.0, 7.0, 8.0]);
+        let expected_result = Matrix4::new([
+            20.0, 22.0, 50.0, 48.0, 44.0, 54.0, 114.0, 108.0, 40.0, 58.0, 110.0, 102.0, 16.0, 26.0, 46.0, 42.0,
+        ]);
 
         assert_eq!(matrix1 * matrix2, expected_result);
     }
 
     #[test]
     fn multiply_matrix_by_tuple() {
-        let matrix = Matrix::new(4, &[1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 4.0, 2.0, 8.0, 6.0, 4.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+        let matrix = Matrix4::new([1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 4.0, 2.0, 8.0, 6.0, 4.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
         let tuple = Tuple::new(1.0, 2.0, 3.0, 1.0);
         let expected_result = Tuple::new(18.0, 24.0, 33.0, 1.0);
 
@@ -248,32 +401,40 @@ mod tests {
 
     #[test]
     fn multiply_matrix_by_identity_matrix() {
-        let matrix1 = Matrix::new(4, &[0.0, 1.0, 2.0, 4.0, 1.0, 2.0, 4.0, 8.0, 2.0, 4.0, 8.0, 16.0, 4.0, 8.0, 16.0, 32.0]);
-        let matrix2 = Matrix::new(4, &[1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
-        let expected_result = Matrix::new(4, &[0.0, 1.0, 2.0, 4.0, 1.0, 2.0, 4.0, 8.0, 2.0, 4.0, 8.0, 16.0, 4.0, 8.0, 16.0, 32.0]);
+        let matrix1 = Matrix4::new([0.0, 1.0, 2.0, 4.0, 1.0, 2.0, 4.0, 8.0, 2.0, 4.0, 8.0, 16.0, 4.0, 8.0, 16.0, 32.0]);
+        let matrix2 = Matrix4::new([1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+        let expected_result = Matrix4::new([0.0, 1.0, 2.0, 4.0, 1.0, 2.0, 4.0, 8.0, 2.0, 4.0, 8.0, 16.0, 4.0, 8.0, 16.0, 32.0]);
 
         assert_eq!(matrix1 * matrix2, expected_result);
     }
 
     #[test]
     fn multiply_identity_matrix_by_tuple() {
-        let matrix = Matrix::new(4, &[1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+        let matrix = Matrix4::new([1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
         let tuple = Tuple::new(1.0, 2.0, 3.0, 4.0);
         let expected_result = Tuple::new(1.0, 2.0, 3.0, 4.0);
 
         assert_eq!(matrix * tuple, expected_result);
     }
 
+    #[test]
+    fn multiply_matrix_by_point_and_vector() {
+        let matrix = Matrix4::new([1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 4.0, 2.0, 8.0, 6.0, 4.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+
+        assert_eq!(matrix * Point3::new(1.0, 2.0, 3.0), Point3::new(18.0, 24.0, 33.0));
+        assert_eq!(matrix * Vector3::new(1.0, 2.0, 3.0), Vector3::new(14.0, 22.0, 32.0));
+    }
+
     #[test]
     fn transpose_matrix() {
-        let matrix = Matrix::new(4, &[0.0, 9.0, 3.0, 0.0, 9.0, 8.0, 0.0, 8.0, 1.0, 8.0, 5.0, 3.0, 0.0, 0.0, 5.0, 8.0]);
-        let expected_result = Matrix::new(4, &[0.0, 9.0, 1.0, 0.0, 9.0, 8.0, 8.0, 0.0, 3.0, 0.0, 5.0, 5.0, 0.0, 8.0, 3.0, 8.0]);
+        let matrix = Matrix4::new([0.0, 9.0, 3.0, 0.0, 9.0, 8.0, 0.0, 8.0, 1.0, 8.0, 5.0, 3.0, 0.0, 0.0, 5.0, 8.0]);
+        let expected_result = Matrix4::new([0.0, 9.0, 1.0, 0.0, 9.0, 8.0, 8.0, 0.0, 3.0, 0.0, 5.0, 5.0, 0.0, 8.0, 3.0, 8.0]);
         assert_eq!(matrix.transpose(), expected_result);
     }
 
     #[test]
     fn transpose_identity_matrix() {
-        let matrix = Matrix::new(4, &[1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+        let matrix = Matrix4::new([1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
 
         assert_eq!(matrix.transpose(), matrix);
     }
@@ -295,7 +456,7 @@ mod tests {
 
     #[test]
     fn submatrix_of_4_4_matrix() {
-        let matrix = Matrix::new(4, &[-6.0, 1.0, 1.0, 6.0, -8.0, 5.0, 8.0, 6.0, -1.0, 0.0, 8.0, 2.0, -7.0, 1.0, -1.0, 1.0]);
+        let matrix = Matrix4::new([-6.0, 1.0, 1.0, 6.0, -8.0, 5.0, 8.0, 6.0, -1.0, 0.0, 8.0, 2.0, -7.0, 1.0, -1.0, 1.0]);
         let expected_result = Matrix::new(3, &[-6.0, 1.0, 6.0, -8.0, 8.0, 6.0, -7.0, -1.0, 1.0]);
 
         assert_eq!(matrix.submatrix(2, 1), expected_result);
@@ -331,7 +492,7 @@ mod tests {
 
     #[test]
     fn determinant_of_4_4_matrix() {
-        let matrix = Matrix::new(4, &[-2.0, -8.0, 3.0, 5.0, -3.0, 1.0, 7.0, 3.0, 1.0, 2.0, -9.0, 6.0, -6.0, 7.0, 7.0, -9.0]);
+        let matrix = Matrix4::new([-2.0, -8.0, 3.0, 5.0, -3.0, 1.0, 7.0, 3.0, 1.0, 2.0, -9.0, 6.0, -6.0, 7.0, 7.0, -9.0]);
 
         assert!(floats_equal(matrix.cofactor(0, 0), 690.0));
         assert!(floats_equal(matrix.cofactor(0, 1), 447.0));
@@ -342,7 +503,7 @@ mod tests {
 
     #[test]
     fn matrix_is_invertible() {
-        let matrix = Matrix::new(4, &[6.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 6.0, 4.0, -9.0, 3.0, -7.0, 9.0, 1.0, 7.0, -6.0]);
+        let matrix = Matrix4::new([6.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 6.0, 4.0, -9.0, 3.0, -7.0, 9.0, 1.0, 7.0, -6.0]);
 
         assert!(floats_equal(matrix.det(), -2120.0));
         assert!(matrix.invertible());
@@ -350,7 +511,7 @@ mod tests {
 
     #[test]
     fn matrix_is_not_invertible() {
-        let matrix = Matrix::new(4, &[-4.0, 2.0, -2.0, -3.0, 9.0, 6.0, 2.0, 6.0, 0.0, -5.0, 1.0, -5.0, 0.0, 0.0, 0.0, 0.0]);
+        let matrix = Matrix4::new([-4.0, 2.0, -2.0, -3.0, 9.0, 6.0, 2.0, 6.0, 0.0, -5.0, 1.0, -5.0, 0.0, 0.0, 0.0, 0.0]);
 
         assert!(floats_equal(matrix.det(), 0.0));
         assert!(!matrix.invertible());
@@ -358,7 +519,7 @@ mod tests {
 
     #[test]
     fn inverse_of_matrix() {
-        let matrix = Matrix::new(4, &[-5.0, 2.0, 6.0, -8.0, 1.0, -5.0, 1.0, 8.0, 7.0, 7.0, -6.0, -7.0, 1.0, -3.0, 7.0, 4.0]);
+        let matrix = Matrix4::new([-5.0, 2.0, 6.0, -8.0, 1.0, -5.0, 1.0, 8.0, 7.0, 7.0, -6.0, -7.0, 1.0, -3.0, 7.0, 4.0]);
         let inverse = matrix.inverse();
 
         assert!(floats_equal(matrix.det(), 532.0));
@@ -367,54 +528,55 @@ mod tests {
         assert!(floats_equal(matrix.cofactor(3, 2), 105.0));
         assert!(floats_equal(inverse.at(2, 3), 105.0 / 532.0));
 
-        let expected_result = Matrix::new(
-            4,
-            &[
-                0.21805, 0.45113, 0.24060, -0.04511, -0.80827, -1.45677, -0.44361, 0.52068, -0.07895, -0.22368, -0.05263, 0.19737, -0.52256,
-                -0.81391, -0.30075, 0.30639,
-            ],
-        );
+        let expected_result = Matrix4::new([
+            0.21805, 0.45113, 0.24060, -0.04511, -0.80827, -1.45677, -0.44361, 0.52068, -0.07895, -0.22368, -0.05263, 0.19737, -0.52256, -0.81391,
+            -0.30075, 0.30639,
+        ]);
 
         assert_eq!(inverse, expected_result);
     }
 
     #[test]
     fn inverse_of_matrix_2() {
-        let matrix = Matrix::new(4, &[8.0, -5.0, 9.0, 2.0, 7.0, 5.0, 6.0, 1.0, -6.0, 0.0, 9.0, 6.0, -3.0, 0.0, -9.0, -4.0]);
-        let expected_result = Matrix::new(
-            4,
-            &[
-                -0.15385, -0.15385, -0.28205, -0.53846, -0.07692, 0.12308, 0.02564, 0.03077, 0.35897, 0.35897, 0.43590, 0.92308, -0.69231, -0.69231,
-                -0.76923, -1.92308,
-            ],
-        );
+        let matrix = Matrix4::new([8.0, -5.0, 9.0, 2.0, 7.0, 5.0, 6.0, 1.0, -6.0, 0.0, 9.0, 6.0, -3.0, 0.0, -9.0, -4.0]);
+        let expected_result = Matrix4::new([
+            -0.15385, -0.15385, -0.28205, -0.53846, -0.07692, 0.12308, 0.02564, 0.03077, 0.35897, 0.35897, 0.43590, 0.92308, -0.69231, -0.69231,
+            -0.76923, -1.92308,
+        ]);
 
         assert_eq!(matrix.inverse(), expected_result);
     }
 
     #[test]
     fn inverse_of_matrix_3() {
-        let matrix = Matrix::new(4, &[9.0, 3.0, 0.0, 9.0, -5.0, -2.0, -6.0, -3.0, -4.0, 9.0, 6.0, 4.0, -7.0, 6.0, 6.0, 2.0]);
-        let expected_result = Matrix::new(
-            4,
-            &[
-                -0.04074, -0.07778, 0.14444, -0.22222, -0.07778, 0.03333, 0.36667, -0.33333, -0.02901, -0.14630, -0.10926, 0.12963, 0.17778, 0.06667,
-                -0.26667, 0.33333,
-            ],
-        );
+        let matrix = Matrix4::new([9.0, 3.0, 0.0, 9.0, -5.0, -2.0, -6.0, -3.0, -4.0, 9.0, 6.0, 4.0, -7.0, 6.0, 6.0, 2.0]);
+        let expected_result = Matrix4::new([
+            -0.04074, -0.07778, 0.14444, -0.22222, -0.07778, 0.03333, 0.36667, -0.33333, -0.02901, -0.14630, -0.10926, 0.12963, 0.17778, 0.06667,
+            -0.26667, 0.33333,
+        ]);
 
         assert_eq!(matrix.inverse(), expected_result);
     }
 
     #[test]
     fn multiply_product_by_inverse() {
-        // TODO: Maybe implement ops::Mul with reference to avoid moving matrix
-        let matrix1 = Matrix::new(4, &[3.0, -9.0, 7.0, 3.0, 3.0, -8.0, 2.0, -9.0, -4.0, 4.0, 4.0, 1.0, -6.0, 5.0, -1.0, 1.0]);
-        let matrix1b = Matrix::new(4, &[3.0, -9.0, 7.0, 3.0, 3.0, -8.0, 2.0, -9.0, -4.0, 4.0, 4.0, 1.0, -6.0, 5.0, -1.0, 1.0]);
-        let matrix2 = Matrix::new(4, &[8.0, 2.0, 2.0, 2.0, 3.0, -1.0, 7.0, 0.0, 7.0, 0.0, 5.0, 4.0, 6.0, -2.0, 0.0, 5.0]);
-        let matrix2b = Matrix::new(4, &[8.0, 2.0, 2.0, 2.0, 3.0, -1.0, 7.0, 0.0, 7.0, 0.0, 5.0, 4.0, 6.0, -2.0, 0.0, 5.0]);
+        let matrix1 = Matrix4::new([3.0, -9.0, 7.0, 3.0, 3.0, -8.0, 2.0, -9.0, -4.0, 4.0, 4.0, 1.0, -6.0, 5.0, -1.0, 1.0]);
+        let matrix2 = Matrix4::new([8.0, 2.0, 2.0, 2.0, 3.0, -1.0, 7.0, 0.0, 7.0, 0.0, 5.0, 4.0, 6.0, -2.0, 0.0, 5.0]);
         let matrix3 = matrix1 * matrix2;
 
-        assert_eq!(matrix3 * (matrix2b.inverse()), matrix1b);
+        assert_eq!(matrix3 * matrix2.inverse(), matrix1);
+    }
+
+    #[test]
+    fn closed_form_inverse_matches_cofactor_expansion() {
+        let matrix = Matrix4::new([-5.0, 2.0, 6.0, -8.0, 1.0, -5.0, 1.0, 8.0, 7.0, 7.0, -6.0, -7.0, 1.0, -3.0, 7.0, 4.0]);
+        let inverse = matrix.inverse();
+
+        for row in 0..4 {
+            for col in 0..4 {
+                assert!(floats_equal(inverse.at(col, row), matrix.cofactor(row, col) / matrix.det()));
+            }
+        }
+        assert_eq!(matrix * inverse, Matrix4::identity());
     }
 }
@@ -0,0 +1,377 @@
+use std::io::{self, BufRead, BufReader, Read};
+
+use crate::{group::Group, point::Point3, shape::Shape, smooth_triangle::SmoothTriangle, triangle::Triangle, vector::Vector3};
+
+// Wavefront OBJ import. Supports vertices, vertex normals, faces (polygons are fan triangulated) and named groups.
+// Anything else is skipped and counted in `ignored_lines` instead of failing the whole file.
+#[derive(Debug)]
+pub struct ObjFile {
+    pub vertices: Vec<Point3>,
+    pub normals: Vec<Vector3>,
+    pub default_group: Group,
+    pub groups: Vec<(String, Group)>,
+    pub ignored_lines: usize,
+}
+
+impl ObjFile {
+    pub fn parse<R: Read>(reader: R) -> io::Result<Self> {
+        let mut obj = ObjFile {
+            vertices: Vec::new(),
+            normals: Vec::new(),
+            default_group: Group::new(),
+            groups: Vec::new(),
+            ignored_lines: 0,
+        };
+        // Index into `groups` that faces are currently added to, None for the default group
+        let mut current_group: Option<usize> = None;
+
+        for line in BufReader::new(reader).lines() {
+            let line = line?;
+            let mut tokens = line.split_whitespace();
+
+            let parsed = match tokens.next() {
+                Some("v") => obj.parse_vertex(tokens),
+                Some("vn") => obj.parse_normal(tokens),
+                Some("f") => obj.parse_face(tokens).and_then(|(triangles, normals_found)| {
+                    let group = match current_group {
+                        Some(index) => &mut obj.groups[index].1,
+                        None => &mut obj.default_group,
+                    };
+
+                    for triangle in triangles {
+                        group.add_child(triangle);
+                    }
+
+                    // Faces with normals that don't exist are still added, but flat and counted as ignored
+                    normals_found.then_some(())
+                }),
+                Some("g") => tokens.next().map(|name| {
+                    current_group = Some(obj.group_index(name));
+                }),
+                // Blank lines and comments
+                None => Some(()),
+                Some(token) if token.starts_with('#') => Some(()),
+                _ => None,
+            };
+
+            if parsed.is_none() {
+                obj.ignored_lines += 1;
+            }
+        }
+
+        Ok(obj)
+    }
+
+    // OBJ vertex indices start at 1
+    pub fn vertex(&self, index: usize) -> Option<Point3> {
+        index.checked_sub(1).and_then(|i| self.vertices.get(i)).copied()
+    }
+
+    // Normal indices start at 1 as well
+    pub fn normal(&self, index: usize) -> Option<Vector3> {
+        index.checked_sub(1).and_then(|i| self.normals.get(i)).copied()
+    }
+
+    pub fn group(&self, name: &str) -> Option<&Group> {
+        self.groups.iter().find(|(n, _)| n == name).map(|(_, g)| g)
+    }
+
+    // Everything in the file as a single group, with each named group nested inside it
+    pub fn into_group(self) -> Group {
+        let mut group = self.default_group;
+
+        for (_, g) in self.groups {
+            group.add_child(Box::new(g));
+        }
+
+        group
+    }
+
+    fn group_index(&mut self, name: &str) -> usize {
+        match self.groups.iter().position(|(n, _)| n == name) {
+            Some(index) => index,
+            None => {
+                self.groups.push((name.to_string(), Group::new()));
+                self.groups.len() - 1
+            }
+        }
+    }
+
+    fn parse_vertex<'a>(&mut self, tokens: impl Iterator<Item = &'a str>) -> Option<()> {
+        let coords: Vec<f64> = tokens.map(|t| t.parse().ok()).collect::<Option<_>>()?;
+
+        // An optional fourth w component is allowed but not used
+        if coords.len() < 3 || coords.len() > 4 {
+            return None;
+        }
+
+        self.vertices.push(Point3::new(coords[0], coords[1], coords[2]));
+        Some(())
+    }
+
+    fn parse_normal<'a>(&mut self, tokens: impl Iterator<Item = &'a str>) -> Option<()> {
+        let coords: Vec<f64> = tokens.map(|t| t.parse().ok()).collect::<Option<_>>()?;
+
+        if coords.len() != 3 {
+            return None;
+        }
+
+        self.normals.push(Vector3::new(coords[0], coords[1], coords[2]));
+        Some(())
+    }
+
+    // Face vertices may look like "v", "v/vt", "v//vn" or "v/vt/vn". Texture indices are not used. The normal is None
+    // without a normal index, and Some(None) when the index doesn't refer to a normal in the file.
+    fn parse_face_vertex(&self, token: &str) -> Option<(Point3, Option<Option<Vector3>>)> {
+        let mut indices = token.split('/');
+        let point = indices.next().and_then(|i| i.parse().ok()).and_then(|i| self.vertex(i))?;
+        let normal = indices.nth(1).map(|i| i.parse().ok().and_then(|i| self.normal(i)));
+
+        Some((point, normal))
+    }
+
+    // The triangles of the face, and whether all the normals it refers to were found
+    fn parse_face<'a>(&self, tokens: impl Iterator<Item = &'a str>) -> Option<(Vec<Box<dyn Shape>>, bool)> {
+        let vertices: Vec<(Point3, Option<Option<Vector3>>)> = tokens.map(|t| self.parse_face_vertex(t)).collect::<Option<_>>()?;
+
+        if vertices.len() < 3 {
+            return None;
+        }
+
+        let points: Vec<Point3> = vertices.iter().map(|(p, _)| *p).collect();
+        let normals_found = vertices.iter().all(|(_, n)| *n != Some(None));
+        // Smooth triangles are only possible when every vertex of the face has a normal
+        let normals: Option<Vec<Vector3>> = vertices.iter().map(|(_, n)| n.flatten()).collect();
+
+        let triangles = (1..points.len() - 1)
+            .map(|i| -> Box<dyn Shape> {
+                match &normals {
+                    Some(n) => Box::new(SmoothTriangle::new(points[0], points[i], points[i + 1], n[0], n[i], n[i + 1])),
+                    None => Box::new(Triangle::new(points[0], points[i], points[i + 1])),
+                }
+            })
+            .collect();
+
+        Some((triangles, normals_found))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle_at(group: &Group, index: usize) -> &Triangle {
+        group.children()[index].as_any().downcast_ref::<Triangle>().unwrap()
+    }
+
+    fn smooth_triangle_at(group: &Group, index: usize) -> &SmoothTriangle {
+        group.children()[index].as_any().downcast_ref::<SmoothTriangle>().unwrap()
+    }
+
+    #[test]
+    fn ignoring_unrecognized_lines() {
+        let gibberish = "There was a young lady named Bright
+who traveled much faster than light.
+She set out one day
+in a relative way,
+and came back the previous night.";
+        let parser = ObjFile::parse(gibberish.as_bytes()).unwrap();
+
+        assert_eq!(parser.ignored_lines, 5);
+    }
+
+    #[test]
+    fn vertex_records() {
+        let file = "v -1 1 0
+v -1.0000 0.5000 0.0000
+v 1 0 0
+v 1 1 0";
+        let parser = ObjFile::parse(file.as_bytes()).unwrap();
+
+        assert_eq!(parser.vertex(1), Some(Point3::new(-1.0, 1.0, 0.0)));
+        assert_eq!(parser.vertex(2), Some(Point3::new(-1.0, 0.5, 0.0)));
+        assert_eq!(parser.vertex(3), Some(Point3::new(1.0, 0.0, 0.0)));
+        assert_eq!(parser.vertex(4), Some(Point3::new(1.0, 1.0, 0.0)));
+        assert_eq!(parser.vertex(0), None);
+        assert_eq!(parser.ignored_lines, 0);
+    }
+
+    #[test]
+    fn parsing_triangle_faces() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+f 1 2 3
+f 1 3 4";
+        let parser = ObjFile::parse(file.as_bytes()).unwrap();
+        let g = &parser.default_group;
+        let t1 = triangle_at(g, 0);
+        let t2 = triangle_at(g, 1);
+
+        assert_eq!(t1.p1, parser.vertex(1).unwrap());
+        assert_eq!(t1.p2, parser.vertex(2).unwrap());
+        assert_eq!(t1.p3, parser.vertex(3).unwrap());
+        assert_eq!(t2.p1, parser.vertex(1).unwrap());
+        assert_eq!(t2.p2, parser.vertex(3).unwrap());
+        assert_eq!(t2.p3, parser.vertex(4).unwrap());
+    }
+
+    #[test]
+    fn triangulating_polygons() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+v 0 2 0
+
+f 1 2 3 4 5";
+        let parser = ObjFile::parse(file.as_bytes()).unwrap();
+        let g = &parser.default_group;
+        let t1 = triangle_at(g, 0);
+        let t2 = triangle_at(g, 1);
+        let t3 = triangle_at(g, 2);
+
+        assert_eq!(g.children().len(), 3);
+        assert_eq!(t1.p1, parser.vertex(1).unwrap());
+        assert_eq!(t1.p2, parser.vertex(2).unwrap());
+        assert_eq!(t1.p3, parser.vertex(3).unwrap());
+        assert_eq!(t2.p1, parser.vertex(1).unwrap());
+        assert_eq!(t2.p2, parser.vertex(3).unwrap());
+        assert_eq!(t2.p3, parser.vertex(4).unwrap());
+        assert_eq!(t3.p1, parser.vertex(1).unwrap());
+        assert_eq!(t3.p2, parser.vertex(4).unwrap());
+        assert_eq!(t3.p3, parser.vertex(5).unwrap());
+    }
+
+    #[test]
+    fn triangles_in_groups() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+        let parser = ObjFile::parse(file.as_bytes()).unwrap();
+        let g1 = parser.group("FirstGroup").unwrap();
+        let g2 = parser.group("SecondGroup").unwrap();
+        let t1 = triangle_at(g1, 0);
+        let t2 = triangle_at(g2, 0);
+
+        assert_eq!(t1.p1, parser.vertex(1).unwrap());
+        assert_eq!(t1.p2, parser.vertex(2).unwrap());
+        assert_eq!(t1.p3, parser.vertex(3).unwrap());
+        assert_eq!(t2.p1, parser.vertex(1).unwrap());
+        assert_eq!(t2.p2, parser.vertex(3).unwrap());
+        assert_eq!(t2.p3, parser.vertex(4).unwrap());
+    }
+
+    #[test]
+    fn faces_with_texture_indices() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+f 1/1 2/2 3/3";
+        let parser = ObjFile::parse(file.as_bytes()).unwrap();
+        let t = triangle_at(&parser.default_group, 0);
+
+        assert_eq!(t.p1, parser.vertex(1).unwrap());
+        assert_eq!(t.p2, parser.vertex(2).unwrap());
+        assert_eq!(t.p3, parser.vertex(3).unwrap());
+    }
+
+    #[test]
+    fn vertex_normal_records() {
+        let file = "vn 0 0 1
+vn 0.707 0 -0.707
+vn 1 2 3";
+        let parser = ObjFile::parse(file.as_bytes()).unwrap();
+
+        assert_eq!(parser.normal(1), Some(Vector3::new(0.0, 0.0, 1.0)));
+        assert_eq!(parser.normal(2), Some(Vector3::new(0.707, 0.0, -0.707)));
+        assert_eq!(parser.normal(3), Some(Vector3::new(1.0, 2.0, 3.0)));
+        assert_eq!(parser.ignored_lines, 0);
+    }
+
+    #[test]
+    fn faces_with_normals() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+
+vn -1 0 0
+vn 1 0 0
+vn 0 1 0
+
+f 1//3 2//1 3//2
+f 1/0/3 2/102/1 3/14/2";
+        let parser = ObjFile::parse(file.as_bytes()).unwrap();
+        let g = &parser.default_group;
+        let t1 = smooth_triangle_at(g, 0);
+        let t2 = smooth_triangle_at(g, 1);
+
+        assert_eq!(t1.p1, parser.vertex(1).unwrap());
+        assert_eq!(t1.p2, parser.vertex(2).unwrap());
+        assert_eq!(t1.p3, parser.vertex(3).unwrap());
+        assert_eq!(t1.n1, parser.normal(3).unwrap());
+        assert_eq!(t1.n2, parser.normal(1).unwrap());
+        assert_eq!(t1.n3, parser.normal(2).unwrap());
+        assert_eq!(t2, t1);
+    }
+
+    #[test]
+    fn faces_with_missing_normals_are_flat() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+vn 0 1 0
+f 1//1 2//2 3//3
+f 1//1 2//1 3//x";
+        let parser = ObjFile::parse(file.as_bytes()).unwrap();
+        let g = &parser.default_group;
+        let t1 = triangle_at(g, 0);
+        let t2 = triangle_at(g, 1);
+
+        assert_eq!(g.children().len(), 2);
+        assert_eq!(t1.p1, parser.vertex(1).unwrap());
+        assert_eq!(t1.p2, parser.vertex(2).unwrap());
+        assert_eq!(t1.p3, parser.vertex(3).unwrap());
+        assert_eq!(t2, t1);
+        assert_eq!(parser.ignored_lines, 2);
+    }
+
+    #[test]
+    fn malformed_records_are_ignored() {
+        let file = "v -1 1 0
+v -1 0
+v 1 0 zero
+f 1 2 3
+f 1 1
+g";
+        let parser = ObjFile::parse(file.as_bytes()).unwrap();
+
+        assert_eq!(parser.vertices.len(), 1);
+        assert!(parser.default_group.children().is_empty());
+        assert_eq!(parser.ignored_lines, 5);
+    }
+
+    #[test]
+    fn converting_obj_file_to_group() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+        let parser = ObjFile::parse(file.as_bytes()).unwrap();
+        let g = parser.into_group();
+
+        assert_eq!(g.children().len(), 2);
+        assert!(g.children().iter().all(|c| c.as_any().is::<Group>()));
+    }
+}
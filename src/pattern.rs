@@ -0,0 +1,336 @@
+use std::fmt::Debug;
+
+use crate::{color::Color, matrix::Matrix4, point::Point3, shape::Shape, transformation::Transform};
+
+pub trait Pattern: Debug + Send + Sync {
+    fn transform(&self) -> &Transform;
+
+    fn set_transform(&mut self, transform: Matrix4);
+
+    // Color at a point that was already transformed into pattern space
+    fn local_pattern_at(&self, point: &Point3) -> Color;
+
+    fn pattern_at_shape(&self, object: &dyn Shape, world_point: &Point3) -> Color {
+        let object_point = object.world_to_object(world_point);
+        let pattern_point = self.transform().inverse() * object_point;
+        self.local_pattern_at(&pattern_point)
+    }
+}
+
+#[derive(Debug)]
+pub struct StripePattern {
+    pub a: Color,
+    pub b: Color,
+    transform: Transform,
+}
+
+impl StripePattern {
+    pub fn new(a: Color, b: Color) -> Self {
+        StripePattern {
+            a,
+            b,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for StripePattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = Transform::new(transform);
+    }
+
+    fn local_pattern_at(&self, point: &Point3) -> Color {
+        if (point.x.floor() as i64).rem_euclid(2) == 0 {
+            self.a
+        } else {
+            self.b
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GradientPattern {
+    pub a: Color,
+    pub b: Color,
+    transform: Transform,
+}
+
+impl GradientPattern {
+    pub fn new(a: Color, b: Color) -> Self {
+        GradientPattern {
+            a,
+            b,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for GradientPattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = Transform::new(transform);
+    }
+
+    fn local_pattern_at(&self, point: &Point3) -> Color {
+        let distance = self.b - self.a;
+        let fraction = point.x - point.x.floor();
+        self.a + distance * fraction
+    }
+}
+
+#[derive(Debug)]
+pub struct RingPattern {
+    pub a: Color,
+    pub b: Color,
+    transform: Transform,
+}
+
+impl RingPattern {
+    pub fn new(a: Color, b: Color) -> Self {
+        RingPattern {
+            a,
+            b,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for RingPattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = Transform::new(transform);
+    }
+
+    fn local_pattern_at(&self, point: &Point3) -> Color {
+        let distance = (point.x * point.x + point.z * point.z).sqrt();
+
+        if (distance.floor() as i64).rem_euclid(2) == 0 {
+            self.a
+        } else {
+            self.b
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CheckersPattern {
+    pub a: Color,
+    pub b: Color,
+    transform: Transform,
+}
+
+impl CheckersPattern {
+    pub fn new(a: Color, b: Color) -> Self {
+        CheckersPattern {
+            a,
+            b,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Pattern for CheckersPattern {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = Transform::new(transform);
+    }
+
+    fn local_pattern_at(&self, point: &Point3) -> Color {
+        let sum = point.x.floor() + point.y.floor() + point.z.floor();
+
+        if (sum as i64).rem_euclid(2) == 0 {
+            self.a
+        } else {
+            self.b
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sphere::Sphere,
+        transformation::{scaling, translation},
+    };
+
+    #[derive(Debug)]
+    struct TestPattern {
+        transform: Transform,
+    }
+
+    impl Pattern for TestPattern {
+        fn transform(&self) -> &Transform {
+            &self.transform
+        }
+
+        fn set_transform(&mut self, transform: Matrix4) {
+            self.transform = Transform::new(transform);
+        }
+
+        fn local_pattern_at(&self, point: &Point3) -> Color {
+            Color::new(point.x, point.y, point.z)
+        }
+    }
+
+    #[test]
+    fn create_stripe_pattern() {
+        let pattern = StripePattern::new(Color::white(), Color::black());
+
+        assert_eq!(pattern.a, Color::white());
+        assert_eq!(pattern.b, Color::black());
+    }
+
+    #[test]
+    fn stripe_pattern_is_constant_in_y() {
+        let pattern = StripePattern::new(Color::white(), Color::black());
+
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 0.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 1.0, 0.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 2.0, 0.0)), Color::white());
+    }
+
+    #[test]
+    fn stripe_pattern_is_constant_in_z() {
+        let pattern = StripePattern::new(Color::white(), Color::black());
+
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 0.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 1.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 2.0)), Color::white());
+    }
+
+    #[test]
+    fn stripe_pattern_alternates_in_x() {
+        let pattern = StripePattern::new(Color::white(), Color::black());
+
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 0.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.9, 0.0, 0.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(1.0, 0.0, 0.0)), Color::black());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(-0.1, 0.0, 0.0)), Color::black());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(-1.0, 0.0, 0.0)), Color::black());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(-1.1, 0.0, 0.0)), Color::white());
+    }
+
+    #[test]
+    fn default_pattern_transformation() {
+        let pattern = StripePattern::new(Color::white(), Color::black());
+
+        assert_eq!(*pattern.transform().matrix(), Matrix4::identity());
+    }
+
+    #[test]
+    fn pattern_with_an_object_transformation() {
+        let mut shape = Sphere::new();
+        shape.set_transform(scaling(2.0, 2.0, 2.0));
+        let pattern = TestPattern {
+            transform: Transform::identity(),
+        };
+        let c = pattern.pattern_at_shape(&shape, &Point3::new(2.0, 3.0, 4.0));
+
+        assert_eq!(c, Color::new(1.0, 1.5, 2.0));
+    }
+
+    #[test]
+    fn pattern_with_a_pattern_transformation() {
+        let shape = Sphere::new();
+        let pattern = TestPattern {
+            transform: Transform::new(scaling(2.0, 2.0, 2.0)),
+        };
+        let c = pattern.pattern_at_shape(&shape, &Point3::new(2.0, 3.0, 4.0));
+
+        assert_eq!(c, Color::new(1.0, 1.5, 2.0));
+    }
+
+    #[test]
+    fn pattern_with_both_an_object_and_a_pattern_transformation() {
+        let mut shape = Sphere::new();
+        shape.set_transform(scaling(2.0, 2.0, 2.0));
+        let pattern = TestPattern {
+            transform: Transform::new(translation(0.5, 1.0, 1.5)),
+        };
+        let c = pattern.pattern_at_shape(&shape, &Point3::new(2.5, 3.0, 3.5));
+
+        assert_eq!(c, Color::new(0.75, 0.5, 0.25));
+    }
+
+    #[test]
+    fn stripes_with_an_object_transformation() {
+        let mut object = Sphere::new();
+        object.set_transform(scaling(2.0, 2.0, 2.0));
+        let pattern = StripePattern::new(Color::white(), Color::black());
+        let c = pattern.pattern_at_shape(&object, &Point3::new(1.5, 0.0, 0.0));
+
+        assert_eq!(c, Color::white());
+    }
+
+    #[test]
+    fn stripes_with_a_pattern_transformation() {
+        let object = Sphere::new();
+        let mut pattern = StripePattern::new(Color::white(), Color::black());
+        pattern.set_transform(scaling(2.0, 2.0, 2.0));
+        let c = pattern.pattern_at_shape(&object, &Point3::new(1.5, 0.0, 0.0));
+
+        assert_eq!(c, Color::white());
+    }
+
+    #[test]
+    fn gradient_linearly_interpolates_between_colors() {
+        let pattern = GradientPattern::new(Color::white(), Color::black());
+
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 0.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.25, 0.0, 0.0)), Color::new(0.75, 0.75, 0.75));
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.5, 0.0, 0.0)), Color::new(0.5, 0.5, 0.5));
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.75, 0.0, 0.0)), Color::new(0.25, 0.25, 0.25));
+    }
+
+    #[test]
+    fn ring_should_extend_in_both_x_and_z() {
+        let pattern = RingPattern::new(Color::white(), Color::black());
+
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 0.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(1.0, 0.0, 0.0)), Color::black());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 1.0)), Color::black());
+        // 0.708 = just slightly more than sqrt(2)/2
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.708, 0.0, 0.708)), Color::black());
+    }
+
+    #[test]
+    fn checkers_should_repeat_in_x() {
+        let pattern = CheckersPattern::new(Color::white(), Color::black());
+
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 0.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.99, 0.0, 0.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(1.01, 0.0, 0.0)), Color::black());
+    }
+
+    #[test]
+    fn checkers_should_repeat_in_y() {
+        let pattern = CheckersPattern::new(Color::white(), Color::black());
+
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 0.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.99, 0.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 1.01, 0.0)), Color::black());
+    }
+
+    #[test]
+    fn checkers_should_repeat_in_z() {
+        let pattern = CheckersPattern::new(Color::white(), Color::black());
+
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 0.0)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 0.99)), Color::white());
+        assert_eq!(pattern.local_pattern_at(&Point3::new(0.0, 0.0, 1.01)), Color::black());
+    }
+}
@@ -0,0 +1,127 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::Intersection;
+use crate::material::Material;
+use crate::point::Point3;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeTransforms};
+use crate::utils::EPSILON;
+use crate::vector::Vector3;
+
+#[derive(Debug, PartialEq)]
+pub struct Plane {
+    transforms: ShapeTransforms,
+    pub material: Material,
+}
+
+impl Plane {
+    pub fn new() -> Self {
+        Plane {
+            transforms: ShapeTransforms::new(),
+            material: Material::new(),
+        }
+    }
+}
+
+impl Shape for Plane {
+    fn transforms(&self) -> &ShapeTransforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut ShapeTransforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        // Parallel and coplanar rays never hit the plane
+        if ray.direction.y.abs() < EPSILON {
+            return Vec::new();
+        }
+
+        let t = -ray.origin.y / ray.direction.y;
+        vec![Intersection::new(t, self)]
+    }
+
+    fn local_normal_at(&self, _point: &Point3) -> Vector3 {
+        Vector3::new(0.0, 1.0, 0.0)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Point3::new(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Point3::new(f64::INFINITY, 0.0, f64::INFINITY),
+        )
+    }
+}
+
+impl Default for Plane {
+    fn default() -> Self {
+        Plane::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utils::floats_equal;
+
+    #[test]
+    fn normal_of_plane_is_constant_everywhere() {
+        let p = Plane::new();
+        let n1 = p.local_normal_at(&Point3::new(0.0, 0.0, 0.0));
+        let n2 = p.local_normal_at(&Point3::new(10.0, 0.0, -10.0));
+        let n3 = p.local_normal_at(&Point3::new(-5.0, 0.0, 150.0));
+
+        assert_eq!(n1, Vector3::new(0.0, 1.0, 0.0));
+        assert_eq!(n2, Vector3::new(0.0, 1.0, 0.0));
+        assert_eq!(n3, Vector3::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn intersect_with_ray_parallel_to_plane() {
+        let p = Plane::new();
+        let r = Ray::new(Point3::new(0.0, 10.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = p.local_intersect(&r);
+
+        assert_eq!(xs.len(), 0);
+    }
+
+    #[test]
+    fn intersect_with_coplanar_ray() {
+        let p = Plane::new();
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = p.local_intersect(&r);
+
+        assert_eq!(xs.len(), 0);
+    }
+
+    #[test]
+    fn ray_intersecting_plane_from_above() {
+        let p = Plane::new();
+        let r = Ray::new(Point3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+        let xs = p.local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert!(floats_equal(xs[0].t, 1.0));
+        assert_eq!(xs[0].object, &p as &dyn Shape);
+    }
+
+    #[test]
+    fn ray_intersecting_plane_from_below() {
+        let p = Plane::new();
+        let r = Ray::new(Point3::new(0.0, -1.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+        let xs = p.local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert!(floats_equal(xs[0].t, 1.0));
+        assert_eq!(xs[0].object, &p as &dyn Shape);
+    }
+}
@@ -0,0 +1,118 @@
+use std::ops;
+
+use crate::tuple::Tuple;
+use crate::utils::floats_equal;
+use crate::vector::Vector3;
+
+// A position in space. Unlike a Tuple it can only be moved by a vector, and two points can only be
+// subtracted from each other (giving the vector between them), never added.
+#[derive(Debug, Copy, Clone)]
+pub struct Point3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Point3 {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn origin() -> Self {
+        Point3::new(0.0, 0.0, 0.0)
+    }
+}
+
+impl Default for Point3 {
+    fn default() -> Self {
+        Point3::origin()
+    }
+}
+
+impl PartialEq for Point3 {
+    fn eq(&self, other: &Self) -> bool {
+        floats_equal(self.x, other.x) && floats_equal(self.y, other.y) && floats_equal(self.z, other.z)
+    }
+}
+
+impl From<Point3> for Tuple {
+    fn from(point: Point3) -> Tuple {
+        Tuple::point(point.x, point.y, point.z)
+    }
+}
+
+impl From<Tuple> for Point3 {
+    fn from(tuple: Tuple) -> Point3 {
+        debug_assert!(tuple.is_point(), "{:?} is not a point", tuple);
+        Point3::new(tuple.x, tuple.y, tuple.z)
+    }
+}
+
+impl ops::Add<Vector3> for Point3 {
+    type Output = Point3;
+
+    fn add(self, other: Vector3) -> Point3 {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl ops::Sub<Vector3> for Point3 {
+    type Output = Point3;
+
+    fn sub(self, other: Vector3) -> Point3 {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl ops::Sub for Point3 {
+    type Output = Vector3;
+
+    fn sub(self, other: Point3) -> Vector3 {
+        Vector3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl ops::Sub for &Point3 {
+    type Output = Vector3;
+
+    fn sub(self, other: &Point3) -> Vector3 {
+        Vector3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adding_vector_to_point_moves_it() {
+        let p = Point3::new(3.0, -2.0, 5.0);
+        let v = Vector3::new(-2.0, 3.0, 1.0);
+
+        assert_eq!(p + v, Point3::new(1.0, 1.0, 6.0));
+    }
+
+    #[test]
+    fn subtract_two_points() {
+        let p1 = Point3::new(3.0, 2.0, 1.0);
+        let p2 = Point3::new(5.0, 6.0, 7.0);
+
+        assert_eq!(p1 - p2, Vector3::new(-2.0, -4.0, -6.0));
+    }
+
+    #[test]
+    fn subtract_vector_from_point() {
+        let p = Point3::new(3.0, 2.0, 1.0);
+        let v = Vector3::new(5.0, 6.0, 7.0);
+
+        assert_eq!(p - v, Point3::new(-2.0, -4.0, -6.0));
+    }
+
+    #[test]
+    fn converting_point_to_and_from_tuple() {
+        let p = Point3::new(1.0, 2.0, 3.0);
+
+        assert_eq!(Tuple::from(p), Tuple::point(1.0, 2.0, 3.0));
+        assert_eq!(Point3::from(Tuple::point(1.0, 2.0, 3.0)), p);
+    }
+}
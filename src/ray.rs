@@ -1,22 +1,23 @@
-use crate::matrix::Matrix;
-use crate::tuple::Tuple;
+use crate::matrix::Matrix4;
+use crate::point::Point3;
+use crate::vector::Vector3;
 
 #[derive(Debug)]
 pub struct Ray {
-    pub origin: Tuple,
-    pub direction: Tuple,
+    pub origin: Point3,
+    pub direction: Vector3,
 }
 
 impl Ray {
-    pub fn new(origin: Tuple, direction: Tuple) -> Self {
+    pub fn new(origin: Point3, direction: Vector3) -> Self {
         Ray { origin, direction }
     }
 
-    pub fn position(&self, t: f64) -> Tuple {
+    pub fn position(&self, t: f64) -> Point3 {
         self.origin + self.direction * t
     }
 
-    pub fn transform(&self, m: &Matrix) -> Self {
+    pub fn transform(&self, m: &Matrix4) -> Self {
         Ray::new(m * self.origin, m * self.direction)
     }
 }
@@ -28,8 +29,8 @@ mod tests {
 
     #[test]
     fn create_ray() {
-        let origin = Tuple::point(1.0, 2.0, 3.0);
-        let direction = Tuple::vector(4.0, 5.0, 6.0);
+        let origin = Point3::new(1.0, 2.0, 3.0);
+        let direction = Vector3::new(4.0, 5.0, 6.0);
         let r = Ray::new(origin, direction);
         assert_eq!(r.origin, origin);
         assert_eq!(r.direction, direction);
@@ -37,28 +38,28 @@ mod tests {
 
     #[test]
     fn compute_point_from_distance() {
-        let r = Ray::new(Tuple::point(2.0, 3.0, 4.0), Tuple::vector(1.0, 0.0, 0.0));
-        assert_eq!(r.position(0.0), Tuple::point(2.0, 3.0, 4.0));
-        assert_eq!(r.position(1.0), Tuple::point(3.0, 3.0, 4.0));
-        assert_eq!(r.position(-1.0), Tuple::point(1.0, 3.0, 4.0));
-        assert_eq!(r.position(2.5), Tuple::point(4.5, 3.0, 4.0));
+        let r = Ray::new(Point3::new(2.0, 3.0, 4.0), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(r.position(0.0), Point3::new(2.0, 3.0, 4.0));
+        assert_eq!(r.position(1.0), Point3::new(3.0, 3.0, 4.0));
+        assert_eq!(r.position(-1.0), Point3::new(1.0, 3.0, 4.0));
+        assert_eq!(r.position(2.5), Point3::new(4.5, 3.0, 4.0));
     }
 
     #[test]
     fn translate_ray() {
-        let r = Ray::new(Tuple::point(1.0, 2.0, 3.0), Tuple::vector(0.0, 1.0, 0.0));
+        let r = Ray::new(Point3::new(1.0, 2.0, 3.0), Vector3::new(0.0, 1.0, 0.0));
         let m = translation(3.0, 4.0, 5.0);
         let r2 = r.transform(&m);
-        assert_eq!(r2.origin, Tuple::point(4.0, 6.0, 8.0));
-        assert_eq!(r2.direction, Tuple::vector(0.0, 1.0, 0.0));
+        assert_eq!(r2.origin, Point3::new(4.0, 6.0, 8.0));
+        assert_eq!(r2.direction, Vector3::new(0.0, 1.0, 0.0));
     }
 
     #[test]
     fn scale_ray() {
-        let r = Ray::new(Tuple::point(1.0, 2.0, 3.0), Tuple::vector(0.0, 1.0, 0.0));
+        let r = Ray::new(Point3::new(1.0, 2.0, 3.0), Vector3::new(0.0, 1.0, 0.0));
         let m = scaling(2.0, 3.0, 4.0);
         let r2 = r.transform(&m);
-        assert_eq!(r2.origin, Tuple::point(2.0, 6.0, 12.0));
-        assert_eq!(r2.direction, Tuple::vector(0.0, 3.0, 0.0));
+        assert_eq!(r2.origin, Point3::new(2.0, 6.0, 12.0));
+        assert_eq!(r2.direction, Vector3::new(0.0, 3.0, 0.0));
     }
 }
@@ -0,0 +1,293 @@
+use rand::Rng;
+
+// Sharpness of the Gaussian filter, higher values fall off faster
+pub const GAUSSIAN_ALPHA: f64 = 2.0;
+
+// Mitchell–Netravali parameters, the pair recommended in the original paper
+pub const MITCHELL_B: f64 = 1.0 / 3.0;
+pub const MITCHELL_C: f64 = 1.0 / 3.0;
+
+// Largest difference in any color channel between neighbouring pixels that adaptive sampling lets slide
+pub const ADAPTIVE_THRESHOLD: f64 = 0.1;
+
+pub const ADAPTIVE_MAX_SAMPLES: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleStrategy {
+    // Samples at the centres of an evenly spaced grid of cells. Always the same, so prone to aliasing of its own.
+    Regular,
+    // One sample at a random spot in each cell of the grid, which keeps the spread of regular sampling without the patterns
+    Jittered,
+    // Samples anywhere in the pixel, so they can clump together
+    Random,
+}
+
+// Reconstruction filter, weighing each sample by its distance from the centre of the pixel it's accumulated into.
+// Filters wider than half a pixel also let samples bleed into the neighbouring pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    Box,
+    Tent,
+    Gaussian,
+    Mitchell,
+}
+
+impl Filter {
+    // How far from a pixel's centre, in pixels, samples still count towards it
+    pub fn radius(&self) -> f64 {
+        match self {
+            Filter::Box => 0.5,
+            Filter::Tent => 1.0,
+            Filter::Gaussian => 1.5,
+            Filter::Mitchell => 2.0,
+        }
+    }
+
+    // Weight of a sample dx and dy pixels away from the centre of the pixel. Mitchell goes slightly negative towards
+    // its edges, which sharpens the image.
+    pub fn weight(&self, dx: f64, dy: f64) -> f64 {
+        self.weight_1d(dx) * self.weight_1d(dy)
+    }
+
+    fn weight_1d(&self, d: f64) -> f64 {
+        let radius = self.radius();
+        let d = d.abs();
+
+        if d > radius {
+            return 0.0;
+        }
+
+        match self {
+            Filter::Box => 1.0,
+            Filter::Tent => radius - d,
+            Filter::Gaussian => ((-GAUSSIAN_ALPHA * d * d).exp() - (-GAUSSIAN_ALPHA * radius * radius).exp()).max(0.0),
+            Filter::Mitchell => mitchell(d),
+        }
+    }
+}
+
+fn mitchell(d: f64) -> f64 {
+    let (b, c) = (MITCHELL_B, MITCHELL_C);
+
+    if d > 1.0 {
+        ((-b - 6.0 * c) * d.powi(3) + (6.0 * b + 30.0 * c) * d.powi(2) + (-12.0 * b - 48.0 * c) * d + (8.0 * b + 24.0 * c)) / 6.0
+    } else {
+        ((12.0 - 9.0 * b - 6.0 * c) * d.powi(3) + (-18.0 + 12.0 * b + 6.0 * c) * d.powi(2) + (6.0 - 2.0 * b)) / 6.0
+    }
+}
+
+// Adaptive sampling takes a single sample per pixel, and then only takes more where neighbouring pixels differ by more
+// than the threshold in any of the color channels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveOptions {
+    pub threshold: f64,
+    // Number of samples refined pixels are sampled with, in place of their first one
+    pub max_samples: usize,
+}
+
+impl AdaptiveOptions {
+    pub fn new() -> Self {
+        AdaptiveOptions {
+            threshold: ADAPTIVE_THRESHOLD,
+            max_samples: ADAPTIVE_MAX_SAMPLES,
+        }
+    }
+}
+
+impl Default for AdaptiveOptions {
+    fn default() -> Self {
+        AdaptiveOptions::new()
+    }
+}
+
+// How the camera samples each pixel. The defaults shoot a single ray through the centre of the pixel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SamplingOptions {
+    pub samples_per_pixel: usize,
+    pub strategy: SampleStrategy,
+    pub filter: Filter,
+    // Renders with the same seed come out exactly the same, no matter how the work is split between threads
+    pub seed: u64,
+    // When set, samples_per_pixel is ignored in favour of sampling only where it's needed
+    pub adaptive: Option<AdaptiveOptions>,
+}
+
+impl SamplingOptions {
+    pub fn new() -> Self {
+        SamplingOptions {
+            samples_per_pixel: 1,
+            strategy: SampleStrategy::Regular,
+            filter: Filter::Box,
+            seed: 0,
+            adaptive: None,
+        }
+    }
+
+    pub fn supersampled(samples_per_pixel: usize, strategy: SampleStrategy, filter: Filter) -> Self {
+        SamplingOptions {
+            samples_per_pixel,
+            strategy,
+            filter,
+            ..SamplingOptions::new()
+        }
+    }
+
+    pub fn adaptive(strategy: SampleStrategy, filter: Filter, adaptive: AdaptiveOptions) -> Self {
+        SamplingOptions {
+            strategy,
+            filter,
+            adaptive: Some(adaptive),
+            ..SamplingOptions::new()
+        }
+    }
+
+    // Seed for the samples of one pixel. The seed is hashed before the index is mixed in, so that nearby seeds don't
+    // give the same noise shifted along by a few pixels.
+    pub fn pixel_seed(&self, index: usize) -> u64 {
+        splitmix64(splitmix64(self.seed) ^ index as u64)
+    }
+
+    // Where in a pixel to take each sample, as offsets from its top-left corner between 0 and 1.
+    // Grid strategies fill rows of ceil(sqrt(n)) cells, with the last row stretched over however many samples are left.
+    pub fn offsets<R: Rng>(&self, rng: &mut R) -> Vec<(f64, f64)> {
+        let count = self.samples_per_pixel.max(1);
+
+        if self.strategy == SampleStrategy::Random {
+            return (0..count).map(|_| (rng.gen::<f64>(), rng.gen::<f64>())).collect();
+        }
+
+        let columns = (count as f64).sqrt().ceil() as usize;
+        let rows = count.div_ceil(columns);
+        let mut offsets = Vec::with_capacity(count);
+
+        for row in 0..rows {
+            let cells = columns.min(count - row * columns);
+
+            for column in 0..cells {
+                let (jx, jy) = match self.strategy {
+                    SampleStrategy::Jittered => (rng.gen::<f64>(), rng.gen::<f64>()),
+                    _ => (0.5, 0.5),
+                };
+
+                offsets.push(((column as f64 + jx) / cells as f64, (row as f64 + jy) / rows as f64));
+            }
+        }
+
+        offsets
+    }
+}
+
+impl Default for SamplingOptions {
+    fn default() -> Self {
+        SamplingOptions::new()
+    }
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::utils::floats_equal;
+
+    #[test]
+    fn default_sampling_shoots_through_pixel_centre() {
+        let options = SamplingOptions::new();
+        let mut rng = StdRng::seed_from_u64(options.seed);
+
+        assert_eq!(options.offsets(&mut rng), vec![(0.5, 0.5)]);
+    }
+
+    #[test]
+    fn regular_sampling_uses_cell_centres() {
+        let options = SamplingOptions::supersampled(4, SampleStrategy::Regular, Filter::Box);
+        let mut rng = StdRng::seed_from_u64(options.seed);
+
+        assert_eq!(options.offsets(&mut rng), vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]);
+    }
+
+    #[test]
+    fn grid_sampling_takes_exactly_the_requested_number_of_samples() {
+        let options = SamplingOptions::supersampled(5, SampleStrategy::Regular, Filter::Box);
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        let offsets = options.offsets(&mut rng);
+
+        assert_eq!(offsets.len(), 5);
+        // The last row only has two samples left, so they split it in half
+        assert_eq!(offsets[3], (0.25, 0.75));
+        assert_eq!(offsets[4], (0.75, 0.75));
+    }
+
+    #[test]
+    fn jittered_samples_stay_within_their_cells() {
+        let options = SamplingOptions::supersampled(9, SampleStrategy::Jittered, Filter::Box);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for (i, (x, y)) in options.offsets(&mut rng).into_iter().enumerate() {
+            let (column, row) = ((i % 3) as f64, (i / 3) as f64);
+
+            assert!(x >= column / 3.0 && x < (column + 1.0) / 3.0);
+            assert!(y >= row / 3.0 && y < (row + 1.0) / 3.0);
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_samples() {
+        let options = SamplingOptions::supersampled(16, SampleStrategy::Random, Filter::Box);
+        let a = options.offsets(&mut StdRng::seed_from_u64(42));
+        let b = options.offsets(&mut StdRng::seed_from_u64(42));
+        let c = options.offsets(&mut StdRng::seed_from_u64(43));
+
+        assert_eq!(a.len(), 16);
+        assert!(a.iter().all(|(x, y)| (0.0..1.0).contains(x) && (0.0..1.0).contains(y)));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn filters_are_zero_outside_their_radius() {
+        for filter in [Filter::Box, Filter::Tent, Filter::Gaussian, Filter::Mitchell] {
+            let r = filter.radius();
+
+            assert!(filter.weight(0.0, 0.0) > 0.0, "{:?}", filter);
+            assert!(floats_equal(filter.weight(r + 0.01, 0.0), 0.0), "{:?}", filter);
+            assert!(floats_equal(filter.weight(0.0, -r - 0.01), 0.0), "{:?}", filter);
+        }
+    }
+
+    #[test]
+    fn filter_weights() {
+        assert!(floats_equal(Filter::Box.weight(0.4, -0.4), 1.0));
+        assert!(floats_equal(Filter::Tent.weight(0.5, 0.0), 0.5));
+        assert!(floats_equal(Filter::Tent.weight(0.5, 0.5), 0.25));
+        assert!(Filter::Gaussian.weight(0.5, 0.0) < Filter::Gaussian.weight(0.25, 0.0));
+        assert!(floats_equal(Filter::Mitchell.weight(0.0, 0.0), (8.0_f64 / 9.0).powi(2)));
+        // Mitchell's negative lobe
+        assert!(Filter::Mitchell.weight(1.5, 0.0) < 0.0);
+    }
+
+    #[test]
+    fn nearby_seeds_are_not_shifted_copies_of_each_other() {
+        let a = SamplingOptions {
+            seed: 3,
+            ..SamplingOptions::new()
+        };
+        let b = SamplingOptions {
+            seed: 4,
+            ..SamplingOptions::new()
+        };
+
+        assert_eq!(a.pixel_seed(10), a.pixel_seed(10));
+        for index in 0..64 {
+            assert_ne!(a.pixel_seed(index + 1), b.pixel_seed(index));
+            assert_ne!(a.pixel_seed(index), b.pixel_seed(index));
+        }
+    }
+}
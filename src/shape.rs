@@ -0,0 +1,302 @@
+use std::any::Any;
+use std::fmt::Debug;
+
+use crate::{
+    bounds::BoundingBox, bvh::BvhOptions, intersection::Intersection, material::Material, matrix::Matrix4, point::Point3, ray::Ray,
+    transformation::Transform, vector::Vector3,
+};
+
+pub trait Shape: ShapeEq + Debug + Send + Sync {
+    fn transforms(&self) -> &ShapeTransforms;
+
+    fn transforms_mut(&mut self) -> &mut ShapeTransforms;
+
+    fn material(&self) -> &Material;
+
+    fn material_mut(&mut self) -> &mut Material;
+
+    // Intersect a ray that was already transformed into object space
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>>;
+
+    // Normal at a point that was already transformed into object space
+    fn local_normal_at(&self, point: &Point3) -> Vector3;
+
+    // Axis-aligned box around the shape in object space
+    fn bounds(&self) -> BoundingBox;
+
+    // Shapes whose normal depends on exactly where they were hit (like smooth triangles) override this
+    fn local_normal_at_hit(&self, point: &Point3, _hit: &Intersection) -> Vector3 {
+        self.local_normal_at(point)
+    }
+
+    fn transform(&self) -> &Matrix4 {
+        self.transforms().local().matrix()
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transforms_mut().set_local(transform);
+    }
+
+    // Combined transform of every group this shape is nested in, identity for shapes outside of a group
+    fn parent_transform(&self) -> &Matrix4 {
+        self.transforms().parent()
+    }
+
+    fn set_parent_transform(&mut self, transform: Matrix4) {
+        self.transforms_mut().set_parent(transform);
+    }
+
+    fn world_transform(&self) -> &Matrix4 {
+        self.transforms().world().matrix()
+    }
+
+    // Bounds in the space of whatever contains the shape, a group or the world
+    fn parent_space_bounds(&self) -> BoundingBox {
+        self.bounds().transform(self.transform())
+    }
+
+    // Shapes that contain other shapes (groups, CSG) build their bounding volume hierarchies here
+    fn build_bvh(&mut self, _options: &BvhOptions) {}
+
+    // Whether `other` is this very shape, containers (groups, CSG) also check everything nested inside them
+    fn includes(&self, other: &dyn Shape) -> bool {
+        std::ptr::eq(self as *const Self as *const u8, other as *const dyn Shape as *const u8)
+    }
+
+    fn intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let local_ray = ray.transform(self.transforms().local().inverse());
+        self.local_intersect(&local_ray)
+    }
+
+    fn normal_at(&self, world_point: &Point3) -> Vector3 {
+        let local_point = self.world_to_object(world_point);
+        let local_normal = self.local_normal_at(&local_point);
+        self.normal_to_world(&local_normal)
+    }
+
+    fn normal_at_hit(&self, world_point: &Point3, hit: &Intersection) -> Vector3 {
+        let local_point = self.world_to_object(world_point);
+        let local_normal = self.local_normal_at_hit(&local_point, hit);
+        self.normal_to_world(&local_normal)
+    }
+
+    fn world_to_object(&self, point: &Point3) -> Point3 {
+        self.transforms().world().inverse() * *point
+    }
+
+    // The inverse transpose would move points, but vectors aren't affected by the translation in it
+    fn normal_to_world(&self, normal: &Vector3) -> Vector3 {
+        (self.transforms().world().inverse_transpose() * *normal).normalized()
+    }
+}
+
+// A shape's own transform, the combined transform of the groups it's nested in, and the two multiplied
+// together. Kept in sync whenever either one changes so the inverses are only ever computed once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeTransforms {
+    local: Transform,
+    parent: Matrix4,
+    world: Transform,
+}
+
+impl ShapeTransforms {
+    pub fn new() -> Self {
+        ShapeTransforms {
+            local: Transform::identity(),
+            parent: Matrix4::identity(),
+            world: Transform::identity(),
+        }
+    }
+
+    pub fn local(&self) -> &Transform {
+        &self.local
+    }
+
+    pub fn parent(&self) -> &Matrix4 {
+        &self.parent
+    }
+
+    pub fn world(&self) -> &Transform {
+        &self.world
+    }
+
+    pub fn set_local(&mut self, transform: Matrix4) {
+        self.local = Transform::new(transform);
+        self.update_world();
+    }
+
+    pub fn set_parent(&mut self, transform: Matrix4) {
+        self.parent = transform;
+        self.update_world();
+    }
+
+    fn update_world(&mut self) {
+        self.world = Transform::new(&self.parent * self.local.matrix());
+    }
+}
+
+impl Default for ShapeTransforms {
+    fn default() -> Self {
+        ShapeTransforms::new()
+    }
+}
+
+// Lets trait objects be compared by value, as long as the underlying shapes are of the same type
+pub trait ShapeEq {
+    fn as_any(&self) -> &dyn Any;
+
+    fn eq_shape(&self, other: &dyn Shape) -> bool;
+}
+
+impl<T: Any + PartialEq> ShapeEq for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_shape(&self, other: &dyn Shape) -> bool {
+        match other.as_any().downcast_ref::<T>() {
+            Some(other) => self == other,
+            None => false,
+        }
+    }
+}
+
+// Identity comparison, for when two equal-valued shapes still need to be told apart
+pub fn same_shape(a: &dyn Shape, b: &dyn Shape) -> bool {
+    std::ptr::eq(a as *const dyn Shape as *const u8, b as *const dyn Shape as *const u8)
+}
+
+impl<'a> PartialEq for dyn Shape + 'a {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_shape(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_1_SQRT_2, PI, SQRT_2};
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{
+        sphere::Sphere,
+        transformation::{rotation_z, scaling, translation},
+    };
+
+    #[derive(Debug)]
+    struct TestShape {
+        transforms: ShapeTransforms,
+        material: Material,
+        saved_ray: Mutex<Option<Ray>>,
+    }
+
+    impl TestShape {
+        fn new() -> Self {
+            TestShape {
+                transforms: ShapeTransforms::new(),
+                material: Material::new(),
+                saved_ray: Mutex::new(None),
+            }
+        }
+    }
+
+    impl PartialEq for TestShape {
+        fn eq(&self, other: &Self) -> bool {
+            self.transforms == other.transforms && self.material == other.material
+        }
+    }
+
+    impl Shape for TestShape {
+        fn transforms(&self) -> &ShapeTransforms {
+            &self.transforms
+        }
+
+        fn transforms_mut(&mut self) -> &mut ShapeTransforms {
+            &mut self.transforms
+        }
+
+        fn material(&self) -> &Material {
+            &self.material
+        }
+
+        fn material_mut(&mut self) -> &mut Material {
+            &mut self.material
+        }
+
+        fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+            *self.saved_ray.lock().unwrap() = Some(Ray::new(ray.origin, ray.direction));
+            Vec::new()
+        }
+
+        fn bounds(&self) -> BoundingBox {
+            BoundingBox::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0))
+        }
+
+        fn local_normal_at(&self, point: &Point3) -> Vector3 {
+            Vector3::new(point.x, point.y, point.z)
+        }
+    }
+
+    #[test]
+    fn intersect_scaled_shape_with_ray() {
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let mut s = TestShape::new();
+        s.set_transform(scaling(2.0, 2.0, 2.0));
+        s.intersect(&r);
+        let saved_ray = s.saved_ray.lock().unwrap().take().unwrap();
+
+        assert_eq!(saved_ray.origin, Point3::new(0.0, 0.0, -2.5));
+        assert_eq!(saved_ray.direction, Vector3::new(0.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn intersect_translated_shape_with_ray() {
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let mut s = TestShape::new();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        s.intersect(&r);
+        let saved_ray = s.saved_ray.lock().unwrap().take().unwrap();
+
+        assert_eq!(saved_ray.origin, Point3::new(-5.0, 0.0, -5.0));
+        assert_eq!(saved_ray.direction, Vector3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn normal_on_translated_shape() {
+        let mut s = TestShape::new();
+        s.set_transform(translation(0.0, 1.0, 0.0));
+        let n = s.normal_at(&Point3::new(0.0, 1.70711, -FRAC_1_SQRT_2));
+
+        assert_eq!(n, Vector3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn normal_on_transformed_shape() {
+        let mut s = TestShape::new();
+        s.set_transform(scaling(1.0, 0.5, 1.0) * rotation_z(PI / 5.0));
+        let n = s.normal_at(&Point3::new(0.0, SQRT_2 / 2.0, -SQRT_2 / 2.0));
+
+        assert_eq!(n, Vector3::new(0.0, 0.97014, -0.24254));
+    }
+
+    #[test]
+    fn setting_transforms_updates_cached_inverses() {
+        let mut t = ShapeTransforms::new();
+        t.set_local(scaling(2.0, 2.0, 2.0));
+        t.set_parent(translation(1.0, 0.0, 0.0));
+
+        assert_eq!(*t.local().inverse(), scaling(0.5, 0.5, 0.5));
+        assert_eq!(*t.world().matrix(), translation(1.0, 0.0, 0.0) * scaling(2.0, 2.0, 2.0));
+        assert_eq!(*t.world().inverse(), scaling(0.5, 0.5, 0.5) * translation(-1.0, 0.0, 0.0));
+        assert_eq!(*t.world().inverse_transpose(), t.world().inverse().transpose());
+    }
+
+    #[test]
+    fn shapes_of_different_types_are_not_equal() {
+        let s1: Box<dyn Shape> = Box::new(TestShape::new());
+        let s2: Box<dyn Shape> = Box::new(Sphere::new());
+
+        assert!(s1 != s2);
+        assert!(s1 == Box::new(TestShape::new()) as Box<dyn Shape>);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::Intersection;
+use crate::material::Material;
+use crate::point::Point3;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeTransforms};
+use crate::triangle::intersect_triangle;
+use crate::vector::Vector3;
+
+// Triangle with a normal per vertex, interpolated across its face using the u and v of each hit
+#[derive(Debug, PartialEq)]
+pub struct SmoothTriangle {
+    transforms: ShapeTransforms,
+    pub material: Material,
+    pub p1: Point3,
+    pub p2: Point3,
+    pub p3: Point3,
+    pub n1: Vector3,
+    pub n2: Vector3,
+    pub n3: Vector3,
+    pub e1: Vector3,
+    pub e2: Vector3,
+}
+
+impl SmoothTriangle {
+    pub fn new(p1: Point3, p2: Point3, p3: Point3, n1: Vector3, n2: Vector3, n3: Vector3) -> Self {
+        SmoothTriangle {
+            transforms: ShapeTransforms::new(),
+            material: Material::new(),
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            e1: p2 - p1,
+            e2: p3 - p1,
+        }
+    }
+}
+
+impl Shape for SmoothTriangle {
+    fn transforms(&self) -> &ShapeTransforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut ShapeTransforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        match intersect_triangle(ray, &self.p1, &self.e1, &self.e2) {
+            Some((t, u, v)) => vec![Intersection::with_uv(t, self, u, v)],
+            None => Vec::new(),
+        }
+    }
+
+    // Without a hit there is nothing to interpolate with, so fall back to the flat face normal
+    fn local_normal_at(&self, _point: &Point3) -> Vector3 {
+        self.e2.cross(self.e1).normalized()
+    }
+
+    fn local_normal_at_hit(&self, _point: &Point3, hit: &Intersection) -> Vector3 {
+        self.n2 * hit.u + self.n3 * hit.v + self.n1 * (1.0 - hit.u - hit.v)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+        bounds.add_point(&self.p1);
+        bounds.add_point(&self.p2);
+        bounds.add_point(&self.p3);
+        bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{intersection::intersections, utils::floats_equal};
+
+    fn test_smooth_triangle() -> SmoothTriangle {
+        SmoothTriangle::new(
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(-1.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn constructing_smooth_triangle() {
+        let tri = test_smooth_triangle();
+
+        assert_eq!(tri.p1, Point3::new(0.0, 1.0, 0.0));
+        assert_eq!(tri.p2, Point3::new(-1.0, 0.0, 0.0));
+        assert_eq!(tri.p3, Point3::new(1.0, 0.0, 0.0));
+        assert_eq!(tri.n1, Vector3::new(0.0, 1.0, 0.0));
+        assert_eq!(tri.n2, Vector3::new(-1.0, 0.0, 0.0));
+        assert_eq!(tri.n3, Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn intersection_with_smooth_triangle_stores_u_and_v() {
+        let tri = test_smooth_triangle();
+        let r = Ray::new(Point3::new(-0.2, 0.3, -2.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = tri.local_intersect(&r);
+
+        assert!(floats_equal(xs[0].u, 0.45));
+        assert!(floats_equal(xs[0].v, 0.25));
+    }
+
+    #[test]
+    fn smooth_triangle_uses_u_and_v_to_interpolate_normal() {
+        let tri = test_smooth_triangle();
+        let i = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+        let n = tri.normal_at_hit(&Point3::new(0.0, 0.0, 0.0), &i);
+
+        assert_eq!(n, Vector3::new(-0.5547, 0.83205, 0.0));
+    }
+
+    #[test]
+    fn preparing_normal_on_smooth_triangle() {
+        let tri = test_smooth_triangle();
+        let i = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+        let r = Ray::new(Point3::new(-0.2, 0.3, -2.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = intersections(&[i]);
+        let comps = i.prepare_computations(&r, &xs);
+
+        assert_eq!(comps.normalv, Vector3::new(-0.5547, 0.83205, 0.0));
+    }
+}
@@ -1,26 +1,52 @@
+use crate::bounds::BoundingBox;
 use crate::intersection::Intersection;
 use crate::material::Material;
-use crate::matrix::Matrix;
+use crate::point::Point3;
 use crate::ray::Ray;
-use crate::tuple::Tuple;
+use crate::shape::{Shape, ShapeTransforms};
+use crate::vector::Vector3;
 
 #[derive(Debug, PartialEq)]
 pub struct Sphere {
-    pub transform: Matrix,
+    transforms: ShapeTransforms,
     pub material: Material,
 }
 
 impl Sphere {
     pub fn new() -> Self {
         Sphere {
-            transform: Matrix::identity(),
+            transforms: ShapeTransforms::new(),
             material: Material::new(),
         }
     }
 
-    pub fn intersect(&self, ray: &Ray) -> Vec<Intersection> {
-        let ray = ray.transform(&self.transform.inverse());
-        let sphere_to_ray = ray.origin - Tuple::point(0.0, 0.0, 0.0);
+    pub fn glass() -> Self {
+        let mut sphere = Sphere::new();
+        sphere.material.transparency = 1.0;
+        sphere.material.refractive_index = 1.5;
+        sphere
+    }
+}
+
+impl Shape for Sphere {
+    fn transforms(&self) -> &ShapeTransforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut ShapeTransforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let sphere_to_ray = ray.origin - Point3::origin();
         let a = ray.direction.dot(&ray.direction);
         let b = 2.0 * ray.direction.dot(&sphere_to_ray);
         let c = sphere_to_ray.dot(&sphere_to_ray) - 1.0;
@@ -36,12 +62,12 @@ impl Sphere {
         }
     }
 
-    pub fn normal_at(&self, world_point: &Tuple) -> Tuple {
-        let object_point = self.transform.inverse() * world_point;
-        let object_normal = object_point - Tuple::point(0.0, 0.0, 0.0);
-        let mut world_normal = self.transform.inverse().transpose() * object_normal;
-        world_normal.w = 0.0;
-        world_normal.normalized()
+    fn local_normal_at(&self, point: &Point3) -> Vector3 {
+        *point - Point3::origin()
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0))
     }
 }
 
@@ -58,13 +84,14 @@ mod tests {
     use super::*;
 
     use crate::{
+        matrix::Matrix4,
         transformation::{rotation_z, scaling, translation},
         utils::floats_equal,
     };
 
     #[test]
     fn ray_intersects_sphere_at_two_points() {
-        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
         let s = Sphere::new();
         let xs = s.intersect(&r);
 
@@ -75,7 +102,7 @@ mod tests {
 
     #[test]
     fn ray_misses_sphere() {
-        let r = Ray::new(Tuple::point(0.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 2.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
         let s = Sphere::new();
         let xs = s.intersect(&r);
 
@@ -84,7 +111,7 @@ mod tests {
 
     #[test]
     fn ray_originates_inside_sphere() {
-        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
         let s = Sphere::new();
         let xs = s.intersect(&r);
 
@@ -95,7 +122,7 @@ mod tests {
 
     #[test]
     fn sphere_behind_ray() {
-        let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, 1.0));
         let s = Sphere::new();
         let xs = s.intersect(&r);
 
@@ -106,36 +133,36 @@ mod tests {
 
     #[test]
     fn intersect_sets_the_object_on_the_intersection() {
-        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
         let s = Sphere::new();
         let xs = s.intersect(&r);
 
         assert_eq!(xs.len(), 2);
-        assert_eq!(xs[0].object, &s);
-        assert_eq!(xs[1].object, &s);
+        assert_eq!(xs[0].object, &s as &dyn Shape);
+        assert_eq!(xs[1].object, &s as &dyn Shape);
     }
 
     #[test]
     fn sphere_default_transformation() {
         let s = Sphere::new();
 
-        assert_eq!(s.transform, Matrix::identity());
+        assert_eq!(*s.transform(), Matrix4::identity());
     }
 
     #[test]
     fn change_sphere_transformation() {
         let mut s = Sphere::new();
         let t = translation(2.0, 3.0, 4.0);
-        s.transform = t;
+        s.set_transform(t);
 
-        assert_eq!(s.transform, translation(2.0, 3.0, 4.0));
+        assert_eq!(*s.transform(), translation(2.0, 3.0, 4.0));
     }
 
     #[test]
     fn intersect_scaled_sphere_with_ray() {
-        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
         let mut s = Sphere::new();
-        s.transform = scaling(2.0, 2.0, 2.0);
+        s.set_transform(scaling(2.0, 2.0, 2.0));
         let xs = s.intersect(&r);
 
         assert_eq!(xs.len(), 2);
@@ -145,9 +172,9 @@ mod tests {
 
     #[test]
     fn intersect_translated_sphere_with_ray() {
-        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
         let mut s = Sphere::new();
-        s.transform = translation(5.0, 0.0, 0.0);
+        s.set_transform(translation(5.0, 0.0, 0.0));
         let xs = s.intersect(&r);
 
         assert_eq!(xs.len(), 0);
@@ -156,39 +183,39 @@ mod tests {
     #[test]
     fn normal_on_sphere_at_point_on_x_axis() {
         let s = Sphere::new();
-        let n = s.normal_at(&Tuple::point(1.0, 0.0, 0.0));
+        let n = s.normal_at(&Point3::new(1.0, 0.0, 0.0));
 
-        assert_eq!(n, Tuple::vector(1.0, 0.0, 0.0));
+        assert_eq!(n, Vector3::new(1.0, 0.0, 0.0));
     }
 
     #[test]
     fn normal_on_sphere_at_point_on_y_axis() {
         let s = Sphere::new();
-        let n = s.normal_at(&Tuple::point(0.0, 1.0, 0.0));
+        let n = s.normal_at(&Point3::new(0.0, 1.0, 0.0));
 
-        assert_eq!(n, Tuple::vector(0.0, 1.0, 0.0));
+        assert_eq!(n, Vector3::new(0.0, 1.0, 0.0));
     }
 
     #[test]
     fn normal_on_sphere_at_point_on_z_axis() {
         let s = Sphere::new();
-        let n = s.normal_at(&Tuple::point(0.0, 0.0, 1.0));
+        let n = s.normal_at(&Point3::new(0.0, 0.0, 1.0));
 
-        assert_eq!(n, Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(n, Vector3::new(0.0, 0.0, 1.0));
     }
 
     #[test]
     fn normal_on_sphere_at_nonaxial_point() {
         let s = Sphere::new();
-        let n = s.normal_at(&Tuple::point(f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0));
+        let n = s.normal_at(&Point3::new(f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0));
 
-        assert_eq!(n, Tuple::vector(f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0));
+        assert_eq!(n, Vector3::new(f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0));
     }
 
     #[test]
     fn normal_is_a_normalized_vector() {
         let s = Sphere::new();
-        let n = s.normal_at(&Tuple::point(f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0));
+        let n = s.normal_at(&Point3::new(f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0, f64::sqrt(3.0) / 3.0));
 
         assert_eq!(n, n.normalized());
     }
@@ -196,19 +223,19 @@ mod tests {
     #[test]
     fn normal_on_translated_sphere() {
         let mut s = Sphere::new();
-        s.transform = translation(0.0, 1.0, 0.0);
-        let n = s.normal_at(&Tuple::point(0.0, 1.70711, -FRAC_1_SQRT_2));
+        s.set_transform(translation(0.0, 1.0, 0.0));
+        let n = s.normal_at(&Point3::new(0.0, 1.70711, -FRAC_1_SQRT_2));
 
-        assert_eq!(n, Tuple::vector(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+        assert_eq!(n, Vector3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
     }
 
     #[test]
     fn normal_on_transformed_sphere() {
         let mut s = Sphere::new();
-        s.transform = scaling(1.0, 0.5, 1.0) * rotation_z(PI / 5.0);
-        let n = s.normal_at(&Tuple::point(0.0, SQRT_2 / 2.0, -SQRT_2 / 2.0));
+        s.set_transform(scaling(1.0, 0.5, 1.0) * rotation_z(PI / 5.0));
+        let n = s.normal_at(&Point3::new(0.0, SQRT_2 / 2.0, -SQRT_2 / 2.0));
 
-        assert_eq!(n, Tuple::vector(0.0, 0.97014, -0.24254));
+        assert_eq!(n, Vector3::new(0.0, 0.97014, -0.24254));
     }
 
     #[test]
@@ -223,8 +250,17 @@ mod tests {
         let mut s = Sphere::new();
         let mut m = Material::new();
         m.ambient = 1.0;
-        s.material = m;
+        s.material = m.clone();
 
         assert_eq!(s.material, m);
     }
+
+    #[test]
+    fn helper_for_producing_a_sphere_with_a_glassy_material() {
+        let s = Sphere::glass();
+
+        assert_eq!(*s.transform(), Matrix4::identity());
+        assert!(floats_equal(s.material.transparency, 1.0));
+        assert!(floats_equal(s.material.refractive_index, 1.5));
+    }
 }
@@ -1,97 +1,142 @@
-use crate::{matrix::Matrix, tuple::Tuple};
+use crate::{matrix::Matrix4, point::Point3, vector::Vector3};
 
-pub fn translation(x: f64, y: f64, z: f64) -> Matrix {
+pub fn translation(x: f64, y: f64, z: f64) -> Matrix4 {
     #[rustfmt::skip]
-    let v = vec![
+    let v = [
         1.0, 0.0, 0.0, x,
         0.0, 1.0, 0.0, y,
         0.0, 0.0, 1.0, z,
         0.0, 0.0, 0.0, 1.0,
     ];
 
-    Matrix::new(4, &v)
+    Matrix4::new(v)
 }
 
-pub fn scaling(x: f64, y: f64, z: f64) -> Matrix {
+pub fn scaling(x: f64, y: f64, z: f64) -> Matrix4 {
     #[rustfmt::skip]
-    let v = vec![
+    let v = [
         x,   0.0, 0.0, 0.0,
         0.0,  y,  0.0, 0.0,
         0.0, 0.0,   z, 0.0,
         0.0, 0.0, 0.0, 1.0,
     ];
 
-    Matrix::new(4, &v)
+    Matrix4::new(v)
 }
 
-pub fn rotation_x(r: f64) -> Matrix {
+pub fn rotation_x(r: f64) -> Matrix4 {
     #[rustfmt::skip]
-    let v = vec![
+    let v = [
         1.0, 0.0, 0.0, 0.0,
         0.0, r.cos(), -r.sin(), 0.0,
         0.0, r.sin(), r.cos(), 0.0,
         0.0, 0.0, 0.0, 1.0
     ];
 
-    Matrix::new(4, &v)
+    Matrix4::new(v)
 }
 
-pub fn rotation_y(r: f64) -> Matrix {
+pub fn rotation_y(r: f64) -> Matrix4 {
     #[rustfmt::skip]
-    let v = vec![
+    let v = [
         r.cos(), 0.0, r.sin(), 0.0,
         0.0, 1.0, 0.0, 0.0,
         -r.sin(), 0.0, r.cos(), 0.0,
         0.0, 0.0, 0.0, 1.0
     ];
 
-    Matrix::new(4, &v)
+    Matrix4::new(v)
 }
 
-pub fn rotation_z(r: f64) -> Matrix {
+pub fn rotation_z(r: f64) -> Matrix4 {
     #[rustfmt::skip]
-    let v = vec![
+    let v = [
         r.cos(), -r.sin(), 0.0, 0.0,
         r.sin(), r.cos(), 0.0, 0.0,
         0.0, 0.0, 1.0, 0.0,
         0.0, 0.0, 0.0, 1.0
     ];
 
-    Matrix::new(4, &v)
+    Matrix4::new(v)
 }
 
-pub fn shearing(xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Matrix {
+pub fn shearing(xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Matrix4 {
     #[rustfmt::skip]
-    let v = vec![
+    let v = [
         1.0, xy, xz, 0.0,
         yx, 1.0, yz, 0.0,
         zx, zy, 1.0, 0.0,
         0.0, 0.0, 0.0, 1.0
     ];
 
-    Matrix::new(4, &v)
+    Matrix4::new(v)
 }
 
-pub fn view_transform(from: Tuple, to: Tuple, up: Tuple) -> Matrix {
+pub fn view_transform(from: Point3, to: Point3, up: Vector3) -> Matrix4 {
     let forward = (to - from).normalized();
     let left = forward.cross(up.normalized());
     let true_up = left.cross(forward);
 
     #[rustfmt::skip]
-    let v = vec![
+    let v = [
         left.x,     left.y,     left.z,     0.0,
         true_up.x,  true_up.y,  true_up.z,  0.0,
         -forward.x, -forward.y, -forward.z, 0.0,
         0.0,        0.0,        0.0,        1.0
     ];
-    let orientation = Matrix::new(4, &v);
+    let orientation = Matrix4::new(v);
 
     orientation * translation(-from.x, -from.y, -from.z)
 }
 
+// A transform along with its inverse and inverse transpose, which are computed once when it's created
+// instead of every time a ray or normal has to be moved between spaces
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transform {
+    matrix: Matrix4,
+    inverse: Matrix4,
+    inverse_transpose: Matrix4,
+}
+
+impl Transform {
+    pub fn new(matrix: Matrix4) -> Self {
+        let inverse = matrix.inverse();
+        let inverse_transpose = inverse.transpose();
+
+        Transform {
+            matrix,
+            inverse,
+            inverse_transpose,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Transform::new(Matrix4::identity())
+    }
+
+    pub fn matrix(&self) -> &Matrix4 {
+        &self.matrix
+    }
+
+    pub fn inverse(&self) -> &Matrix4 {
+        &self.inverse
+    }
+
+    pub fn inverse_transpose(&self) -> &Matrix4 {
+        &self.inverse_transpose
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tuple::Tuple;
 
     use std::f64::consts::{PI, SQRT_2};
 
@@ -260,19 +305,19 @@ mod tests {
 
     #[test]
     fn transformation_matrix_for_default_orientation() {
-        let from = Tuple::point(0.0, 0.0, 0.0);
-        let to = Tuple::point(0.0, 0.0, -1.0);
-        let up = Tuple::vector(0.0, 1.0, 0.0);
+        let from = Point3::new(0.0, 0.0, 0.0);
+        let to = Point3::new(0.0, 0.0, -1.0);
+        let up = Vector3::new(0.0, 1.0, 0.0);
         let t = view_transform(from, to, up);
 
-        assert_eq!(t, Matrix::identity());
+        assert_eq!(t, Matrix4::identity());
     }
 
     #[test]
     fn view_transformation_matrix_looking_in_positive_z_direction() {
-        let from = Tuple::point(0.0, 0.0, 0.0);
-        let to = Tuple::point(0.0, 0.0, 1.0);
-        let up = Tuple::vector(0.0, 1.0, 0.0);
+        let from = Point3::new(0.0, 0.0, 0.0);
+        let to = Point3::new(0.0, 0.0, 1.0);
+        let up = Vector3::new(0.0, 1.0, 0.0);
         let t = view_transform(from, to, up);
 
         assert_eq!(t, scaling(-1.0, 1.0, -1.0));
@@ -280,9 +325,9 @@ mod tests {
 
     #[test]
     fn view_transformation_moves_the_world() {
-        let from = Tuple::point(0.0, 0.0, 8.0);
-        let to = Tuple::point(0.0, 0.0, 0.0);
-        let up = Tuple::vector(0.0, 1.0, 0.0);
+        let from = Point3::new(0.0, 0.0, 8.0);
+        let to = Point3::new(0.0, 0.0, 0.0);
+        let up = Vector3::new(0.0, 1.0, 0.0);
         let t = view_transform(from, to, up);
 
         assert_eq!(t, translation(0.0, 0.0, -8.0));
@@ -290,15 +335,25 @@ mod tests {
 
     #[test]
     fn view_transformation_arbitrary() {
-        let from = Tuple::point(1.0, 3.0, 2.0);
-        let to = Tuple::point(4.0, -2.0, 8.0);
-        let up = Tuple::vector(1.0, 1.0, 0.0);
+        let from = Point3::new(1.0, 3.0, 2.0);
+        let to = Point3::new(4.0, -2.0, 8.0);
+        let up = Vector3::new(1.0, 1.0, 0.0);
         let t = view_transform(from, to, up);
-        let expected_result = vec![
+        let expected_result = [
             -0.50709, 0.50709, 0.67612, -2.36643, 0.76772, 0.60609, 0.12122, -2.82843, -0.35857, 0.59761, -0.71714, 0.00000, 0.00000, 0.00000,
             0.00000, 1.00000,
         ];
 
-        assert_eq!(t, Matrix::new(4, &expected_result));
+        assert_eq!(t, Matrix4::new(expected_result));
+    }
+
+    #[test]
+    fn transform_caches_inverse_and_inverse_transpose() {
+        let m = translation(1.0, 2.0, 3.0) * scaling(2.0, 4.0, 8.0);
+        let t = Transform::new(m);
+
+        assert_eq!(*t.matrix(), m);
+        assert_eq!(*t.inverse(), m.inverse());
+        assert_eq!(*t.inverse_transpose(), m.inverse().transpose());
     }
 }
@@ -0,0 +1,189 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::Intersection;
+use crate::material::Material;
+use crate::point::Point3;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeTransforms};
+use crate::utils::EPSILON;
+use crate::vector::Vector3;
+
+// The edges and normal are derived from the three points when the triangle is created
+#[derive(Debug, PartialEq)]
+pub struct Triangle {
+    transforms: ShapeTransforms,
+    pub material: Material,
+    pub p1: Point3,
+    pub p2: Point3,
+    pub p3: Point3,
+    pub e1: Vector3,
+    pub e2: Vector3,
+    pub normal: Vector3,
+}
+
+impl Triangle {
+    pub fn new(p1: Point3, p2: Point3, p3: Point3) -> Self {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+        let normal = e2.cross(e1).normalized();
+
+        Triangle {
+            transforms: ShapeTransforms::new(),
+            material: Material::new(),
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal,
+        }
+    }
+}
+
+// Möller–Trumbore intersection, returning t along with the barycentric u and v of the hit
+pub(crate) fn intersect_triangle(ray: &Ray, p1: &Point3, e1: &Vector3, e2: &Vector3) -> Option<(f64, f64, f64)> {
+    let (origin, direction) = (ray.origin, ray.direction);
+    let dir_cross_e2 = direction.cross(*e2);
+    let det = e1.dot(&dir_cross_e2);
+
+    // The ray is parallel to the triangle
+    if det.abs() < EPSILON {
+        return None;
+    }
+
+    let f = 1.0 / det;
+    let p1_to_origin = origin - *p1;
+    let u = f * p1_to_origin.dot(&dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let origin_cross_e1 = p1_to_origin.cross(*e1);
+    let v = f * direction.dot(&origin_cross_e1);
+    if v < 0.0 || (u + v) > 1.0 {
+        return None;
+    }
+
+    let t = f * e2.dot(&origin_cross_e1);
+    Some((t, u, v))
+}
+
+impl Shape for Triangle {
+    fn transforms(&self) -> &ShapeTransforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut ShapeTransforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        match intersect_triangle(ray, &self.p1, &self.e1, &self.e2) {
+            Some((t, u, v)) => vec![Intersection::with_uv(t, self, u, v)],
+            None => Vec::new(),
+        }
+    }
+
+    fn local_normal_at(&self, _point: &Point3) -> Vector3 {
+        self.normal
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+        bounds.add_point(&self.p1);
+        bounds.add_point(&self.p2);
+        bounds.add_point(&self.p3);
+        bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utils::floats_equal;
+
+    fn test_triangle() -> Triangle {
+        Triangle::new(Point3::new(0.0, 1.0, 0.0), Point3::new(-1.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0))
+    }
+
+    #[test]
+    fn constructing_triangle() {
+        let p1 = Point3::new(0.0, 1.0, 0.0);
+        let p2 = Point3::new(-1.0, 0.0, 0.0);
+        let p3 = Point3::new(1.0, 0.0, 0.0);
+        let t = Triangle::new(p1, p2, p3);
+
+        assert_eq!(t.p1, p1);
+        assert_eq!(t.p2, p2);
+        assert_eq!(t.p3, p3);
+        assert_eq!(t.e1, Vector3::new(-1.0, -1.0, 0.0));
+        assert_eq!(t.e2, Vector3::new(1.0, -1.0, 0.0));
+        assert_eq!(t.normal, Vector3::new(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn finding_normal_on_triangle() {
+        let t = test_triangle();
+        let n1 = t.local_normal_at(&Point3::new(0.0, 0.5, 0.0));
+        let n2 = t.local_normal_at(&Point3::new(-0.5, 0.75, 0.0));
+        let n3 = t.local_normal_at(&Point3::new(0.5, 0.25, 0.0));
+
+        assert_eq!(n1, t.normal);
+        assert_eq!(n2, t.normal);
+        assert_eq!(n3, t.normal);
+    }
+
+    #[test]
+    fn intersecting_ray_parallel_to_triangle() {
+        let t = test_triangle();
+        let r = Ray::new(Point3::new(0.0, -1.0, -2.0), Vector3::new(0.0, 1.0, 0.0));
+        let xs = t.local_intersect(&r);
+
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn ray_misses_p1_p3_edge() {
+        let t = test_triangle();
+        let r = Ray::new(Point3::new(1.0, 1.0, -2.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = t.local_intersect(&r);
+
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn ray_misses_p1_p2_edge() {
+        let t = test_triangle();
+        let r = Ray::new(Point3::new(-1.0, 1.0, -2.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = t.local_intersect(&r);
+
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn ray_misses_p2_p3_edge() {
+        let t = test_triangle();
+        let r = Ray::new(Point3::new(0.0, -1.0, -2.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = t.local_intersect(&r);
+
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn ray_strikes_triangle() {
+        let t = test_triangle();
+        let r = Ray::new(Point3::new(0.0, 0.5, -2.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = t.local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert!(floats_equal(xs[0].t, 2.0));
+    }
+}
@@ -1,3 +1,13 @@
+use crate::point::Point3;
+
+pub const EPSILON: f64 = 0.00001;
+
 pub fn floats_equal(one: f64, two: f64) -> bool {
-    (one - two).abs() < 0.00001
+    (one - two).abs() < EPSILON
+}
+
+// Seed for random numbers that only depend on where in the scene they're used, so renders are reproducible
+// however the work is split between threads
+pub fn point_seed(point: &Point3) -> u64 {
+    point.x.to_bits() ^ point.y.to_bits().rotate_left(21) ^ point.z.to_bits().rotate_left(42)
 }
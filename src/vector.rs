@@ -0,0 +1,187 @@
+use std::ops;
+
+use crate::tuple::Tuple;
+use crate::utils::floats_equal;
+
+// A direction with a length. Matrices apply to it without translation, since it has no position.
+#[derive(Debug, Copy, Clone)]
+pub struct Vector3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vector3 {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Vector3 { x, y, z }
+    }
+
+    pub fn zero() -> Self {
+        Vector3::new(0.0, 0.0, 0.0)
+    }
+
+    pub fn magnitude(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalized(&self) -> Self {
+        *self / self.magnitude()
+    }
+
+    pub fn dot(&self, other: &Vector3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: Vector3) -> Self {
+        Vector3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn reflect(&self, normal: &Vector3) -> Self {
+        self - &(normal * 2.0 * self.dot(normal))
+    }
+}
+
+impl Default for Vector3 {
+    fn default() -> Self {
+        Vector3::zero()
+    }
+}
+
+impl PartialEq for Vector3 {
+    fn eq(&self, other: &Self) -> bool {
+        floats_equal(self.x, other.x) && floats_equal(self.y, other.y) && floats_equal(self.z, other.z)
+    }
+}
+
+impl From<Vector3> for Tuple {
+    fn from(vector: Vector3) -> Tuple {
+        Tuple::vector(vector.x, vector.y, vector.z)
+    }
+}
+
+impl From<Tuple> for Vector3 {
+    fn from(tuple: Tuple) -> Vector3 {
+        debug_assert!(tuple.is_vector(), "{:?} is not a vector", tuple);
+        Vector3::new(tuple.x, tuple.y, tuple.z)
+    }
+}
+
+impl ops::Add for Vector3 {
+    type Output = Vector3;
+
+    fn add(self, other: Vector3) -> Vector3 {
+        Vector3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl ops::Add for &Vector3 {
+    type Output = Vector3;
+
+    fn add(self, other: &Vector3) -> Vector3 {
+        Vector3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl ops::Sub for Vector3 {
+    type Output = Vector3;
+
+    fn sub(self, other: Vector3) -> Vector3 {
+        Vector3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl ops::Sub for &Vector3 {
+    type Output = Vector3;
+
+    fn sub(self, other: &Vector3) -> Vector3 {
+        Vector3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl ops::Neg for Vector3 {
+    type Output = Vector3;
+
+    fn neg(self) -> Vector3 {
+        Vector3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl ops::Mul<f64> for Vector3 {
+    type Output = Vector3;
+
+    fn mul(self, other: f64) -> Vector3 {
+        Vector3::new(self.x * other, self.y * other, self.z * other)
+    }
+}
+
+impl ops::Mul<f64> for &Vector3 {
+    type Output = Vector3;
+
+    fn mul(self, other: f64) -> Vector3 {
+        Vector3::new(self.x * other, self.y * other, self.z * other)
+    }
+}
+
+impl ops::Div<f64> for Vector3 {
+    type Output = Vector3;
+
+    fn div(self, other: f64) -> Vector3 {
+        Vector3::new(self.x / other, self.y / other, self.z / other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::SQRT_2;
+
+    use super::*;
+
+    #[test]
+    fn add_and_subtract_vectors() {
+        let v1 = Vector3::new(3.0, 2.0, 1.0);
+        let v2 = Vector3::new(5.0, 6.0, 7.0);
+
+        assert_eq!(v1 + v2, Vector3::new(8.0, 8.0, 8.0));
+        assert_eq!(v1 - v2, Vector3::new(-2.0, -4.0, -6.0));
+        assert_eq!(-v1, Vector3::new(-3.0, -2.0, -1.0));
+    }
+
+    #[test]
+    fn magnitude_and_normalizing() {
+        let v = Vector3::new(1.0, 2.0, 3.0);
+
+        assert!(floats_equal(v.magnitude(), 14.0_f64.sqrt()));
+        assert!(floats_equal(v.normalized().magnitude(), 1.0));
+        assert_eq!(Vector3::new(4.0, 0.0, 0.0).normalized(), Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn dot_and_cross_products() {
+        let v1 = Vector3::new(1.0, 2.0, 3.0);
+        let v2 = Vector3::new(2.0, 3.0, 4.0);
+
+        assert!(floats_equal(v1.dot(&v2), 20.0));
+        assert_eq!(v1.cross(v2), Vector3::new(-1.0, 2.0, -1.0));
+        assert_eq!(v2.cross(v1), Vector3::new(1.0, -2.0, 1.0));
+    }
+
+    #[test]
+    fn reflect_vector_off_slanted_surface() {
+        let v = Vector3::new(0.0, -1.0, 0.0);
+        let n = Vector3::new(SQRT_2 / 2.0, SQRT_2 / 2.0, 0.0);
+
+        assert_eq!(v.reflect(&n), Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn converting_vector_to_and_from_tuple() {
+        let v = Vector3::new(1.0, 2.0, 3.0);
+
+        assert_eq!(Tuple::from(v), Tuple::vector(1.0, 2.0, 3.0));
+        assert_eq!(Vector3::from(Tuple::vector(1.0, 2.0, 3.0)), v);
+    }
+}
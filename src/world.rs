@@ -1,27 +1,56 @@
+use std::f64::consts::PI;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
 use crate::{
+    background::Background,
+    bounds::BoundingBox,
+    bvh::{Bvh, BvhOptions, BvhStats},
     color::Color,
     intersection::{hit, Computations, Intersection},
-    light::PointLight,
+    light::{Light, LightSample, PointLight},
+    point::Point3,
     ray::Ray,
+    shape::Shape,
     sphere::Sphere,
     transformation,
-    tuple::Tuple,
+    utils::{floats_equal, point_seed},
+    vector::Vector3,
 };
 
+// How many times a ray may bounce between reflective surfaces before giving up
+pub const MAX_RECURSION_DEPTH: usize = 5;
+
 #[derive(Debug)]
 pub struct World {
-    pub light: Option<PointLight>,
-    pub objects: Vec<Sphere>,
+    pub lights: Vec<Light>,
+    objects: Vec<Box<dyn Shape>>,
+    pub background: Background,
+    // How many directions the background is sampled in to light each point, 0 leaves the lighting to `lights` alone
+    pub background_light_samples: usize,
+    pub max_depth: usize,
+    pub bvh_options: BvhOptions,
+    bvh: Option<Bvh>,
 }
 
 impl World {
     pub fn new() -> Self {
         World {
-            light: None,
+            lights: Vec::new(),
             objects: Vec::new(),
+            background: Background::Constant(Color::black()),
+            background_light_samples: 0,
+            max_depth: MAX_RECURSION_DEPTH,
+            bvh_options: BvhOptions::new(),
+            bvh: None,
         }
     }
 
+    pub fn with_bvh_options(bvh_options: BvhOptions) -> Self {
+        World { bvh_options, ..World::new() }
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> Self {
         let mut s1 = Sphere::new();
         s1.material.color = Color::new(0.8, 1.0, 0.6);
@@ -29,39 +58,189 @@ impl World {
         s1.material.specular = 0.2;
 
         let mut s2 = Sphere::new();
-        s2.transform = transformation::scaling(0.5, 0.5, 0.5);
+        s2.set_transform(transformation::scaling(0.5, 0.5, 0.5));
 
         World {
-            light: Some(PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0))),
-            objects: vec![s1, s2],
+            lights: vec![PointLight::new(Point3::new(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0)).into()],
+            objects: vec![Box::new(s1), Box::new(s2)],
+            background: Background::Constant(Color::black()),
+            background_light_samples: 0,
+            max_depth: MAX_RECURSION_DEPTH,
+            bvh_options: BvhOptions::new(),
+            bvh: None,
         }
     }
 
-    pub fn intersect(&self, ray: &Ray) -> Vec<Intersection> {
+    pub fn objects(&self) -> &[Box<dyn Shape>] {
+        &self.objects
+    }
+
+    // Adding, removing or changing objects drops the bounding volume hierarchy, since it would no longer match them.
+    // Rays are tested against every object until build_bvh is called again.
+    pub fn add_object(&mut self, object: Box<dyn Shape>) {
+        self.bvh = None;
+        self.objects.push(object);
+    }
+
+    pub fn remove_object(&mut self, index: usize) -> Box<dyn Shape> {
+        self.bvh = None;
+        self.objects.remove(index)
+    }
+
+    pub fn object_mut(&mut self, index: usize) -> &mut dyn Shape {
+        self.bvh = None;
+        self.objects[index].as_mut()
+    }
+
+    // Builds bounding volume hierarchies over the objects and inside every group
+    pub fn build_bvh(&mut self) {
+        for object in self.objects.iter_mut() {
+            object.build_bvh(&self.bvh_options);
+        }
+
+        let bounds: Vec<BoundingBox> = self.objects.iter().map(|object| object.parent_space_bounds()).collect();
+        self.bvh = Some(Bvh::build(&bounds, &self.bvh_options));
+    }
+
+    // Stats of the hierarchy over the world's objects, not including the ones inside groups
+    pub fn bvh_stats(&self) -> Option<BvhStats> {
+        self.bvh.as_ref().map(|bvh| bvh.stats())
+    }
+
+    pub fn intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
         let mut intersections = Vec::new();
 
-        for object in &self.objects {
-            for intersection in object.intersect(ray) {
-                intersections.push(intersection);
+        match &self.bvh {
+            Some(bvh) => bvh.traverse(ray, |i| intersections.extend(self.objects[i].intersect(ray))),
+            None => {
+                for object in self.objects.iter() {
+                    intersections.extend(object.intersect(ray));
+                }
             }
         }
 
-        intersections.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+        intersections.sort_by(|a, b| a.t.total_cmp(&b.t));
 
         intersections
     }
 
-    pub fn shade_hit(&self, comps: Computations) -> Color {
-        comps
-            .object
-            .material
-            .lighting(self.light.unwrap(), comps.point, comps.eyev, comps.normalv)
+    pub fn shade_hit(&self, comps: Computations, remaining: usize) -> Color {
+        let material = comps.object.material();
+
+        // Every light adds its own contribution, dimmed by however much of it is hidden from the point. With no lights at
+        // all only the ambient term is left.
+        let mut surface = if self.lights.is_empty() {
+            material.ambient_color(comps.object, comps.over_point)
+        } else {
+            self.lights.iter().fold(Color::black(), |color, light| {
+                let visibility = self.intensity_at(light, comps.over_point);
+                color + material.lighting(comps.object, light, comps.over_point, comps.eyev, comps.normalv, visibility)
+            })
+        };
+        if self.background_light_samples > 0 {
+            surface = surface + self.background_lighting(&comps);
+        }
+        let reflected = self.reflected_color(&comps, remaining);
+        let refracted = self.refracted_color(&comps, remaining);
+
+        // Surfaces that both reflect and refract blend the two by the Fresnel reflectance
+        if material.reflective > 0.0 && material.transparency > 0.0 {
+            let reflectance = comps.schlick();
+            return surface + reflected * reflectance + refracted * (1.0 - reflectance);
+        }
+
+        surface + reflected + refracted
     }
 
-    pub fn color_at(&self, ray: &Ray) -> Color {
-        match hit(&self.intersect(ray)) {
-            Some(xs) => self.shade_hit(xs.prepare_computations(ray)),
-            None => Color::black(),
+    pub fn color_at(&self, ray: &Ray, remaining: usize) -> Color {
+        let xs = self.intersect(ray);
+
+        match hit(&xs) {
+            Some(i) => self.shade_hit(i.prepare_computations(ray, &xs), remaining),
+            None => self.background.color_at(ray.direction),
+        }
+    }
+
+    // Diffuse light from the background, averaged over cosine-weighted directions around the normal that aren't
+    // blocked by anything. A uniform background of intensity 1 lights a surface like a light straight above it would.
+    pub fn background_lighting(&self, comps: &Computations) -> Color {
+        let material = comps.object.material();
+        let normal = comps.normalv;
+        // Any vector that isn't parallel to the normal gives a basis around it
+        let helper = if normal.x.abs() > 0.9 {
+            Vector3::new(0.0, 1.0, 0.0)
+        } else {
+            Vector3::new(1.0, 0.0, 0.0)
+        };
+        let tangent = normal.cross(helper).normalized();
+        let bitangent = normal.cross(tangent);
+        let mut rng = StdRng::seed_from_u64(point_seed(&comps.over_point));
+        let mut sum = Color::black();
+
+        for _ in 0..self.background_light_samples {
+            let (phi, r2) = (2.0 * PI * rng.gen::<f64>(), rng.gen::<f64>());
+            let r = r2.sqrt();
+            let direction = tangent * (r * phi.cos()) + bitangent * (r * phi.sin()) + normal * (1.0 - r2).sqrt();
+
+            if hit(&self.intersect(&Ray::new(comps.over_point, direction))).is_none() {
+                sum = sum + self.background.color_at(direction);
+            }
+        }
+
+        material.color_at(comps.object, comps.over_point) * material.diffuse * (sum * (1.0 / self.background_light_samples as f64))
+    }
+
+    pub fn reflected_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let reflective = comps.object.material().reflective;
+
+        if remaining == 0 || floats_equal(reflective, 0.0) {
+            return Color::black();
+        }
+
+        let reflect_ray = Ray::new(comps.over_point, comps.reflectv);
+        self.color_at(&reflect_ray, remaining - 1) * reflective
+    }
+
+    pub fn refracted_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let transparency = comps.object.material().transparency;
+
+        if remaining == 0 || floats_equal(transparency, 0.0) {
+            return Color::black();
+        }
+
+        // Snell's law
+        let n_ratio = comps.n1 / comps.n2;
+        let cos_i = comps.eyev.dot(&comps.normalv);
+        let sin2_t = n_ratio.powi(2) * (1.0 - cos_i.powi(2));
+
+        // Total internal reflection
+        if sin2_t > 1.0 {
+            return Color::black();
+        }
+
+        let cos_t = (1.0 - sin2_t).sqrt();
+        let direction = comps.normalv * (n_ratio * cos_i - cos_t) - comps.eyev * n_ratio;
+        let refract_ray = Ray::new(comps.under_point, direction);
+
+        self.color_at(&refract_ray, remaining - 1) * transparency
+    }
+
+    // Fraction of the light's samples that the point can see, from 0 in full shadow to 1 fully lit
+    pub fn intensity_at(&self, light: &Light, point: Point3) -> f64 {
+        let samples = light.samples(point);
+        let visible = samples.iter().filter(|sample| !self.is_shadowed(point, sample)).count();
+
+        visible as f64 / samples.len() as f64
+    }
+
+    // Whether anything is between the point and the light the sample was taken of
+    pub fn is_shadowed(&self, point: Point3, sample: &LightSample) -> bool {
+        let r = Ray::new(point, sample.direction);
+        let intersections = self.intersect(&r);
+
+        match hit(&intersections) {
+            Some(h) => h.t < sample.distance,
+            None => false,
         }
     }
 }
@@ -74,20 +253,26 @@ impl Default for World {
 
 #[cfg(test)]
 mod tests {
+    use std::f64::consts::{PI, SQRT_2};
+
     use super::*;
-    use crate::utils::floats_equal;
+    use crate::{
+        bvh::{SplitMethod, SAH_BINS},
+        light::{AreaLight, DirectionalLight, SpotLight},
+        plane::Plane,
+    };
 
     #[test]
     fn create_world() {
         let w = World::new();
 
-        assert_eq!(w.objects.len(), 0);
-        assert_eq!(w.light, None);
+        assert_eq!(w.objects().len(), 0);
+        assert!(w.lights.is_empty());
     }
 
     #[test]
     fn default_world() {
-        let light = PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let light = PointLight::new(Point3::new(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
 
         let mut s1 = Sphere::new();
         s1.material.color = Color::new(0.8, 1.0, 0.6);
@@ -95,19 +280,33 @@ mod tests {
         s1.material.specular = 0.2;
 
         let mut s2 = Sphere::new();
-        s2.transform = transformation::scaling(0.5, 0.5, 0.5);
+        s2.set_transform(transformation::scaling(0.5, 0.5, 0.5));
 
         let w = World::default();
 
-        assert_eq!(w.light.unwrap(), light);
-        assert!(w.objects.contains(&s1));
-        assert!(w.objects.contains(&s2));
+        assert_eq!(w.lights, vec![light.into()]);
+        assert!(w.objects().iter().any(|o| o.as_ref() == &s1 as &dyn Shape));
+        assert!(w.objects().iter().any(|o| o.as_ref() == &s2 as &dyn Shape));
     }
 
     #[test]
     fn intersect_world_with_ray() {
         let w = World::default();
-        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = w.intersect(&r);
+
+        assert_eq!(xs.len(), 4);
+        assert!(floats_equal(xs[0].t, 4.0));
+        assert!(floats_equal(xs[1].t, 4.5));
+        assert!(floats_equal(xs[2].t, 5.5));
+        assert!(floats_equal(xs[3].t, 6.0));
+    }
+
+    #[test]
+    fn intersect_world_with_bvh() {
+        let mut w = World::default();
+        w.build_bvh();
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
         let xs = w.intersect(&r);
 
         assert_eq!(xs.len(), 4);
@@ -117,14 +316,69 @@ mod tests {
         assert!(floats_equal(xs[3].t, 6.0));
     }
 
+    #[test]
+    fn world_built_with_sah_bvh() {
+        let mut w = World::with_bvh_options(BvhOptions::sah());
+        for i in 0..10 {
+            let mut s = Sphere::new();
+            s.set_transform(transformation::translation(0.0, 0.0, i as f64 * 3.0));
+            w.add_object(Box::new(s));
+        }
+        w.build_bvh();
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = w.intersect(&r);
+
+        assert_eq!(w.bvh_options.split, SplitMethod::Sah { bins: SAH_BINS });
+        assert_eq!(xs.len(), 20);
+        assert!(floats_equal(xs[0].t, 4.0));
+        assert!(w.bvh_stats().unwrap().node_count > 1);
+    }
+
+    #[test]
+    fn objects_added_after_building_bvh_are_still_intersected() {
+        let mut w = World::default();
+        w.build_bvh();
+        let mut s = Sphere::new();
+        s.set_transform(transformation::translation(0.0, 0.0, 10.0));
+        w.add_object(Box::new(s));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert!(w.bvh_stats().is_none());
+        assert_eq!(w.intersect(&r).len(), 6);
+    }
+
+    #[test]
+    fn removing_objects_after_building_bvh() {
+        let mut w = World::default();
+        w.build_bvh();
+        w.remove_object(0);
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = w.intersect(&r);
+
+        assert_eq!(xs.len(), 2);
+        assert!(floats_equal(xs[0].t, 4.5));
+    }
+
+    #[test]
+    fn objects_moved_after_building_bvh_are_still_intersected() {
+        let mut w = World::default();
+        w.build_bvh();
+        w.object_mut(1).set_transform(transformation::translation(0.0, 0.0, 10.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = w.intersect(&r);
+
+        assert_eq!(xs.len(), 4);
+        assert!(floats_equal(xs[2].t, 14.0));
+    }
+
     #[test]
     fn shading_an_intersection() {
         let w = World::default();
-        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
-        let shape = &w.objects[0];
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let shape = w.objects()[0].as_ref();
         let i = Intersection::new(4.0, shape);
-        let comps = i.prepare_computations(&r);
-        let c = w.shade_hit(comps);
+        let comps = i.prepare_computations(&r, &[i]);
+        let c = w.shade_hit(comps, MAX_RECURSION_DEPTH);
 
         assert_eq!(c, Color::new(0.38066, 0.47583, 0.2855));
     }
@@ -132,12 +386,12 @@ mod tests {
     #[test]
     fn shading_an_intersection_from_the_inside() {
         let mut w = World::default();
-        w.light = Some(PointLight::new(Tuple::point(0.0, 0.25, 0.0), Color::new(1.0, 1.0, 1.0)));
-        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
-        let shape = &w.objects[1];
+        w.lights = vec![PointLight::new(Point3::new(0.0, 0.25, 0.0), Color::new(1.0, 1.0, 1.0)).into()];
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+        let shape = w.objects()[1].as_ref();
         let i = Intersection::new(0.5, shape);
-        let comps = i.prepare_computations(&r);
-        let c = w.shade_hit(comps);
+        let comps = i.prepare_computations(&r, &[i]);
+        let c = w.shade_hit(comps, MAX_RECURSION_DEPTH);
 
         assert_eq!(c, Color::new(0.90498, 0.90498, 0.90498));
     }
@@ -145,8 +399,8 @@ mod tests {
     #[test]
     fn color_when_ray_misses() {
         let w = World::default();
-        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
-        let c = w.color_at(&r);
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 1.0, 0.0));
+        let c = w.color_at(&r, MAX_RECURSION_DEPTH);
 
         assert_eq!(c, Color::new(0.0, 0.0, 0.0));
     }
@@ -154,8 +408,8 @@ mod tests {
     #[test]
     fn color_when_ray_hits() {
         let w = World::default();
-        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
-        let c = w.color_at(&r);
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let c = w.color_at(&r, MAX_RECURSION_DEPTH);
 
         assert_eq!(c, Color::new(0.38066, 0.47583, 0.2855));
     }
@@ -163,14 +417,384 @@ mod tests {
     #[test]
     fn color_when_an_intersection_behind_the_ray() {
         let mut w = World::default();
-        let expected_result = w.objects[1].material.color;
-        let mut outer = &mut w.objects[0];
-        outer.material.ambient = 1.0;
-        let mut inner = &mut w.objects[1];
-        inner.material.ambient = 1.0;
-        let r = Ray::new(Tuple::point(0.0, 0.0, 0.75), Tuple::vector(0.0, 0.0, -1.0));
-        let c = w.color_at(&r);
+        let expected_result = w.objects()[1].material().color;
+        w.object_mut(0).material_mut().ambient = 1.0;
+        w.object_mut(1).material_mut().ambient = 1.0;
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.75), Vector3::new(0.0, 0.0, -1.0));
+        let c = w.color_at(&r, MAX_RECURSION_DEPTH);
 
         assert_eq!(c, expected_result);
     }
+
+    #[test]
+    fn no_shadow_when_nothing_is_collinear_with_point_and_light() {
+        let w = World::default();
+        let p = Point3::new(0.0, 10.0, 0.0);
+
+        assert!(!w.is_shadowed(p, &w.lights[0].samples(p)[0]));
+    }
+
+    #[test]
+    fn shadow_when_an_object_is_between_the_point_and_the_light() {
+        let w = World::default();
+        let p = Point3::new(10.0, -10.0, 10.0);
+
+        assert!(w.is_shadowed(p, &w.lights[0].samples(p)[0]));
+    }
+
+    #[test]
+    fn no_shadow_when_an_object_is_behind_the_light() {
+        let w = World::default();
+        let p = Point3::new(-20.0, 20.0, -20.0);
+
+        assert!(!w.is_shadowed(p, &w.lights[0].samples(p)[0]));
+    }
+
+    #[test]
+    fn no_shadow_when_an_object_is_behind_the_point() {
+        let w = World::default();
+        let p = Point3::new(-2.0, 2.0, -2.0);
+
+        assert!(!w.is_shadowed(p, &w.lights[0].samples(p)[0]));
+    }
+
+    #[test]
+    fn shade_hit_is_given_an_intersection_in_shadow() {
+        let mut w = World::new();
+        w.lights = vec![PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0)).into()];
+        let s1 = Sphere::new();
+        let mut s2 = Sphere::new();
+        s2.set_transform(transformation::translation(0.0, 0.0, 10.0));
+        w.add_object(Box::new(s1));
+        w.add_object(Box::new(s2));
+        let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, 1.0));
+        let i = Intersection::new(4.0, w.objects()[1].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+        let c = w.shade_hit(comps, MAX_RECURSION_DEPTH);
+
+        assert_eq!(c, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn shading_with_no_lights_leaves_only_ambient() {
+        let mut w = World::default();
+        w.lights.clear();
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let shape = w.objects()[0].as_ref();
+        let i = Intersection::new(4.0, shape);
+        let comps = i.prepare_computations(&r, &[i]);
+        let c = w.shade_hit(comps, MAX_RECURSION_DEPTH);
+
+        assert_eq!(c, Color::new(0.08, 0.1, 0.06));
+    }
+
+    #[test]
+    fn shading_adds_up_every_light() {
+        let mut w = World::default();
+        w.lights.push(w.lights[0]);
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let shape = w.objects()[0].as_ref();
+        let i = Intersection::new(4.0, shape);
+        let comps = i.prepare_computations(&r, &[i]);
+        let c = w.shade_hit(comps, MAX_RECURSION_DEPTH);
+
+        assert_eq!(c, Color::new(0.38066, 0.47583, 0.2855) * 2.0);
+    }
+
+    #[test]
+    fn each_light_is_tested_for_shadows_separately() {
+        let mut w = World::new();
+        w.lights = vec![
+            PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::white()).into(),
+            PointLight::new(Point3::new(0.0, 0.0, 5.0), Color::white()).into(),
+        ];
+        let mut s2 = Sphere::new();
+        s2.set_transform(transformation::translation(0.0, 0.0, 10.0));
+        w.add_object(Box::new(Sphere::new()));
+        w.add_object(Box::new(s2));
+        let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, 1.0));
+        let i = Intersection::new(4.0, w.objects()[1].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+
+        assert!(w.is_shadowed(comps.over_point, &w.lights[0].samples(comps.over_point)[0]));
+        assert!(!w.is_shadowed(comps.over_point, &w.lights[1].samples(comps.over_point)[0]));
+
+        // Ambient from the blocked light, everything from the one facing the point
+        let c = w.shade_hit(comps, MAX_RECURSION_DEPTH);
+        assert_eq!(c, Color::new(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn intensity_at_with_a_point_light() {
+        let w = World::default();
+        let light = w.lights[0];
+        let cases = [
+            (Point3::new(0.0, 1.0001, 0.0), 1.0),
+            (Point3::new(-1.0001, 0.0, 0.0), 1.0),
+            (Point3::new(0.0, 0.0, -1.0001), 1.0),
+            (Point3::new(0.0, 0.0, 1.0001), 0.0),
+            (Point3::new(1.0001, 0.0, 0.0), 0.0),
+            (Point3::new(0.0, -1.0001, 0.0), 0.0),
+            (Point3::new(0.0, 0.0, 0.0), 0.0),
+        ];
+
+        for (point, expected) in cases.iter() {
+            assert!(floats_equal(w.intensity_at(&light, *point), *expected));
+        }
+    }
+
+    #[test]
+    fn intensity_at_with_an_area_light() {
+        let w = World::default();
+        let mut area = AreaLight::new(
+            Point3::new(-0.5, -0.5, -5.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            2,
+            Vector3::new(0.0, 1.0, 0.0),
+            2,
+            Color::white(),
+        );
+        area.jitter = false;
+        let light = Light::from(area);
+        let cases = [
+            (Point3::new(0.0, 0.0, 2.0), 0.0),
+            (Point3::new(1.0, -1.0, 2.0), 0.25),
+            (Point3::new(1.5, 0.0, 2.0), 0.5),
+            (Point3::new(1.25, 1.25, 3.0), 0.75),
+            (Point3::new(0.0, 0.0, -2.0), 1.0),
+        ];
+
+        for (point, expected) in cases.iter() {
+            assert!(floats_equal(w.intensity_at(&light, *point), *expected));
+        }
+    }
+
+    #[test]
+    fn directional_light_is_blocked_by_objects_at_any_distance() {
+        let mut w = World::new();
+        w.lights = vec![DirectionalLight::new(Vector3::new(0.0, -1.0, 0.0), Color::white()).into()];
+        let mut s = Sphere::new();
+        s.set_transform(transformation::translation(0.0, 1000.0, 0.0));
+        w.add_object(Box::new(s));
+
+        assert!(floats_equal(w.intensity_at(&w.lights[0], Point3::new(0.0, 0.0, 0.0)), 0.0));
+        assert!(floats_equal(w.intensity_at(&w.lights[0], Point3::new(5.0, 0.0, 0.0)), 1.0));
+    }
+
+    #[test]
+    fn spot_light_only_lights_its_cone() {
+        let mut w = World::new();
+        w.lights = vec![SpotLight::new(Point3::new(0.0, 10.0, 0.0), Vector3::new(0.0, -1.0, 0.0), PI / 8.0, 0.0, Color::white()).into()];
+        let mut floor = Plane::new();
+        floor.material.specular = 0.0;
+        w.add_object(Box::new(floor));
+        let lit = w.color_at(&Ray::new(Point3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)), MAX_RECURSION_DEPTH);
+        let unlit = w.color_at(&Ray::new(Point3::new(10.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)), MAX_RECURSION_DEPTH);
+
+        assert_eq!(lit, Color::new(1.0, 1.0, 1.0));
+        assert_eq!(unlit, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn ray_that_misses_sees_the_background() {
+        let mut w = World::default();
+        w.background = Background::Gradient {
+            bottom: Color::black(),
+            top: Color::white(),
+        };
+
+        assert_eq!(
+            w.color_at(&Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 1.0, 0.0)), MAX_RECURSION_DEPTH),
+            Color::white()
+        );
+        assert_eq!(
+            w.color_at(&Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, -1.0)), MAX_RECURSION_DEPTH),
+            Color::new(0.5, 0.5, 0.5)
+        );
+    }
+
+    #[test]
+    fn background_lights_surfaces_that_can_see_it() {
+        let mut w = World::new();
+        w.background = Background::Constant(Color::white());
+        w.background_light_samples = 16;
+        w.add_object(Box::new(Plane::new()));
+        let c = w.color_at(&Ray::new(Point3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)), MAX_RECURSION_DEPTH);
+
+        // Ambient with no lights, plus the whole diffuse term from the background
+        assert_eq!(c, Color::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn background_light_is_blocked_by_surrounding_objects() {
+        let mut w = World::new();
+        w.background = Background::Constant(Color::white());
+        w.background_light_samples = 16;
+        let mut dome = Sphere::new();
+        dome.set_transform(transformation::scaling(10.0, 10.0, 10.0));
+        w.add_object(Box::new(Plane::new()));
+        w.add_object(Box::new(dome));
+        let c = w.color_at(&Ray::new(Point3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)), MAX_RECURSION_DEPTH);
+
+        assert_eq!(c, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn reflected_color_for_a_nonreflective_material() {
+        let mut w = World::default();
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+        w.object_mut(1).material_mut().ambient = 1.0;
+        let i = Intersection::new(1.0, w.objects()[1].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+        let color = w.reflected_color(&comps, MAX_RECURSION_DEPTH);
+
+        assert_eq!(color, Color::black());
+    }
+
+    #[test]
+    fn reflected_color_for_a_reflective_material() {
+        let mut w = World::default();
+        let mut shape = Plane::new();
+        shape.material.reflective = 0.5;
+        shape.set_transform(transformation::translation(0.0, -1.0, 0.0));
+        w.add_object(Box::new(shape));
+        let r = Ray::new(Point3::new(0.0, 0.0, -3.0), Vector3::new(0.0, -SQRT_2 / 2.0, SQRT_2 / 2.0));
+        let i = Intersection::new(SQRT_2, w.objects()[2].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+        let color = w.reflected_color(&comps, MAX_RECURSION_DEPTH);
+
+        assert_eq!(color, Color::new(0.19033, 0.23791, 0.14274));
+    }
+
+    #[test]
+    fn shade_hit_with_a_reflective_material() {
+        let mut w = World::default();
+        let mut shape = Plane::new();
+        shape.material.reflective = 0.5;
+        shape.set_transform(transformation::translation(0.0, -1.0, 0.0));
+        w.add_object(Box::new(shape));
+        let r = Ray::new(Point3::new(0.0, 0.0, -3.0), Vector3::new(0.0, -SQRT_2 / 2.0, SQRT_2 / 2.0));
+        let i = Intersection::new(SQRT_2, w.objects()[2].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+        let color = w.shade_hit(comps, MAX_RECURSION_DEPTH);
+
+        assert_eq!(color, Color::new(0.87676, 0.92434, 0.82917));
+    }
+
+    #[test]
+    fn color_at_with_mutually_reflective_surfaces() {
+        let mut w = World::new();
+        w.lights = vec![PointLight::new(Point3::new(0.0, 0.0, 0.0), Color::white()).into()];
+        let mut lower = Plane::new();
+        lower.material.reflective = 1.0;
+        lower.set_transform(transformation::translation(0.0, -1.0, 0.0));
+        let mut upper = Plane::new();
+        upper.material.reflective = 1.0;
+        upper.set_transform(transformation::translation(0.0, 1.0, 0.0));
+        w.add_object(Box::new(lower));
+        w.add_object(Box::new(upper));
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+
+        // Terminates instead of recursing forever
+        w.color_at(&r, MAX_RECURSION_DEPTH);
+    }
+
+    #[test]
+    fn reflected_color_at_the_maximum_recursive_depth() {
+        let mut w = World::default();
+        let mut shape = Plane::new();
+        shape.material.reflective = 0.5;
+        shape.set_transform(transformation::translation(0.0, -1.0, 0.0));
+        w.add_object(Box::new(shape));
+        let r = Ray::new(Point3::new(0.0, 0.0, -3.0), Vector3::new(0.0, -SQRT_2 / 2.0, SQRT_2 / 2.0));
+        let i = Intersection::new(SQRT_2, w.objects()[2].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+        let color = w.reflected_color(&comps, 0);
+
+        assert_eq!(color, Color::black());
+    }
+
+    #[test]
+    fn refracted_color_with_an_opaque_surface() {
+        let w = World::default();
+        let shape = w.objects()[0].as_ref();
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = [Intersection::new(4.0, shape), Intersection::new(6.0, shape)];
+        let comps = xs[0].prepare_computations(&r, &xs);
+        let c = w.refracted_color(&comps, 5);
+
+        assert_eq!(c, Color::black());
+    }
+
+    #[test]
+    fn refracted_color_at_the_maximum_recursive_depth() {
+        let mut w = World::default();
+        w.object_mut(0).material_mut().transparency = 1.0;
+        w.object_mut(0).material_mut().refractive_index = 1.5;
+        let shape = w.objects()[0].as_ref();
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let xs = [Intersection::new(4.0, shape), Intersection::new(6.0, shape)];
+        let comps = xs[0].prepare_computations(&r, &xs);
+        let c = w.refracted_color(&comps, 0);
+
+        assert_eq!(c, Color::black());
+    }
+
+    #[test]
+    fn refracted_color_under_total_internal_reflection() {
+        let mut w = World::default();
+        w.object_mut(0).material_mut().transparency = 1.0;
+        w.object_mut(0).material_mut().refractive_index = 1.5;
+        let shape = w.objects()[0].as_ref();
+        let r = Ray::new(Point3::new(0.0, 0.0, SQRT_2 / 2.0), Vector3::new(0.0, 1.0, 0.0));
+        let xs = [Intersection::new(-SQRT_2 / 2.0, shape), Intersection::new(SQRT_2 / 2.0, shape)];
+        // Inside the sphere, so look at the second intersection
+        let comps = xs[1].prepare_computations(&r, &xs);
+        let c = w.refracted_color(&comps, 5);
+
+        assert_eq!(c, Color::black());
+    }
+
+    #[test]
+    fn shade_hit_with_a_transparent_material() {
+        let mut w = World::default();
+        let mut floor = Plane::new();
+        floor.set_transform(transformation::translation(0.0, -1.0, 0.0));
+        floor.material.transparency = 0.5;
+        floor.material.refractive_index = 1.5;
+        let mut ball = Sphere::new();
+        ball.material.color = Color::new(1.0, 0.0, 0.0);
+        ball.material.ambient = 0.5;
+        ball.set_transform(transformation::translation(0.0, -3.5, -0.5));
+        w.add_object(Box::new(floor));
+        w.add_object(Box::new(ball));
+        let r = Ray::new(Point3::new(0.0, 0.0, -3.0), Vector3::new(0.0, -SQRT_2 / 2.0, SQRT_2 / 2.0));
+        let xs = [Intersection::new(SQRT_2, w.objects()[2].as_ref())];
+        let comps = xs[0].prepare_computations(&r, &xs);
+        let color = w.shade_hit(comps, 5);
+
+        assert_eq!(color, Color::new(0.93642, 0.68642, 0.68642));
+    }
+
+    #[test]
+    fn shade_hit_with_a_reflective_transparent_material() {
+        let mut w = World::default();
+        let mut floor = Plane::new();
+        floor.set_transform(transformation::translation(0.0, -1.0, 0.0));
+        floor.material.reflective = 0.5;
+        floor.material.transparency = 0.5;
+        floor.material.refractive_index = 1.5;
+        let mut ball = Sphere::new();
+        ball.material.color = Color::new(1.0, 0.0, 0.0);
+        ball.material.ambient = 0.5;
+        ball.set_transform(transformation::translation(0.0, -3.5, -0.5));
+        w.add_object(Box::new(floor));
+        w.add_object(Box::new(ball));
+        let r = Ray::new(Point3::new(0.0, 0.0, -3.0), Vector3::new(0.0, -SQRT_2 / 2.0, SQRT_2 / 2.0));
+        let xs = [Intersection::new(SQRT_2, w.objects()[2].as_ref())];
+        let comps = xs[0].prepare_computations(&r, &xs);
+        let color = w.shade_hit(comps, 5);
+
+        assert_eq!(color, Color::new(0.93391, 0.69643, 0.69243));
+    }
 }